@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ptt_crawler::Screen;
+
+// 前兩個 byte 決定畫面大小，第三個決定每次送進 Screen 的長度 (模擬封包被切開)
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 3 {
+        return;
+    }
+    let width = data[0] as usize % 120 + 1;
+    let height = data[1] as usize % 40 + 1;
+    let chunk_size = data[2] as usize + 1;
+
+    let mut screen = Screen::with_size(width, height);
+    for chunk in data[3 ..].chunks(chunk_size) {
+        screen.process(chunk);
+    }
+    screen.snapshot();
+    screen.to_ansi();
+});
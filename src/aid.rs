@@ -0,0 +1,172 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::screen::Screen;
+
+// 文章代碼 (AID) 每個字元代表 6 bits
+const AID_DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+const AID_LENGTH: usize = 8;
+const URL_PREFIX: &str = "https://www.ptt.cc/bbs/";
+
+// 文章的檔名 (例如 M.1700000001.A.001) 在看板中不會重複，可以拿來當作文章的主鍵
+// 文章代碼是把檔名壓成 8 個字元：種類 4 bits、發文時間 32 bits、最後的十六進位數字 12 bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArticleId {
+    pub kind: char,     // M：一般文章，G：精華區
+    pub timestamp: u32, // 發文時間 (Unix time)
+    pub random: u16     // 檔名最後的三位十六進位數字
+}
+
+impl ArticleId {
+    // "#1bK_4101" 或 "1bK_4101"
+    pub fn from_aid(aid: &str) -> Option<ArticleId> {
+        let aid = aid.strip_prefix('#').unwrap_or(aid);
+        if aid.len() != AID_LENGTH {
+            return None;
+        }
+
+        let mut value: u64 = 0;
+        for b in aid.bytes() {
+            let digit = AID_DIGITS.iter().position(|&d| d == b)?;
+            value = value << 6 | digit as u64;
+        }
+        let kind = match value >> 44 {
+            0 => 'M',
+            1 => 'G',
+            _ => return None
+        };
+        Some(ArticleId {
+            kind,
+            timestamp: (value >> 12) as u32,
+            random: (value & 0xfff) as u16
+        })
+    }
+
+    // "M.1700000001.A.001"
+    pub fn from_filename(filename: &str) -> Option<ArticleId> {
+        let parts: Vec<&str> = filename.split('.').collect();
+        if parts.len() != 4 || parts[2] != "A" || parts[3].is_empty() || parts[3].len() > 3 {
+            return None;
+        }
+        let kind = match parts[0] {
+            "M" => 'M',
+            "G" => 'G',
+            _ => return None
+        };
+        Some(ArticleId {
+            kind,
+            timestamp: parts[1].parse().ok()?,
+            random: u16::from_str_radix(parts[3], 16).ok()?
+        })
+    }
+
+    // 不含開頭的 #
+    pub fn to_aid(&self) -> String {
+        let kind: u64 = if self.kind == 'G' { 1 } else { 0 };
+        let value = kind << 44 | (self.timestamp as u64) << 12 | (self.random & 0xfff) as u64;
+        (0 .. AID_LENGTH).rev().map(|i| AID_DIGITS[(value >> (i * 6) & 63) as usize] as char).collect()
+    }
+
+    pub fn to_filename(&self) -> String {
+        format!("{}.{}.A.{:03X}", self.kind, self.timestamp, self.random)
+    }
+
+    pub fn get_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.timestamp as u64)
+    }
+
+    // 網頁版的網址，例如 https://www.ptt.cc/bbs/Gossiping/M.1700000001.A.001.html
+    pub fn to_url(&self, board: &str) -> String {
+        format!("{}{}/{}.html", URL_PREFIX, board, self.to_filename())
+    }
+}
+
+impl fmt::Display for ArticleId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{}", self.to_aid())
+    }
+}
+
+// 從網頁版的網址取出看板名稱與文章
+pub fn parse_url(url: &str) -> Option<(String, ArticleId)> {
+    let path = url.trim().trim_start_matches("http://").trim_start_matches("https://");
+    let path = path.strip_prefix("www.ptt.cc/bbs/")?;
+    let (board, filename) = path.split_once('/')?;
+    let id = ArticleId::from_filename(filename.strip_suffix(".html")?)?;
+    Some((board.to_string(), id))
+}
+
+// 在文章列表按 Q 出現的文章資訊：
+// │ 文章代碼(AID): #1bK_4101 (Gossiping) [ptt.cc] [問卦] 標題
+// │ 文章網址: https://www.ptt.cc/bbs/Gossiping/M.1700000001.A.001.html
+pub fn parse_article_info(screen: &Screen) -> Option<ArticleId> {
+    for row in 0 .. screen.height() {
+        let text = screen.row_text(row);
+        if let Some(pos) = text.find("文章代碼(AID):") {
+            let rest = text[pos + "文章代碼(AID):".len() ..].trim_start();
+            if let Some(id) = rest.split_whitespace().next().and_then(ArticleId::from_aid) {
+                return Some(id);
+            }
+        }
+        if let Some(pos) = text.find("文章網址:") {
+            let rest = text[pos + "文章網址:".len() ..].trim_start();
+            if let Some((_, id)) = rest.split_whitespace().next().and_then(parse_url) {
+                return Some(id);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{parse_article_info, parse_url, ArticleId};
+    use crate::screen::{Big5, Screen, ScreenEncoding};
+
+    #[test]
+    fn aid_and_filename_convert_both_ways() {
+        let id = ArticleId::from_filename("M.1700000001.A.001").unwrap();
+        assert_eq!(id.to_aid(), "1bK_4101");
+        assert_eq!(id.to_string(), "#1bK_4101");
+        assert_eq!(ArticleId::from_aid("#1bK_4101"), Some(id));
+        assert_eq!(ArticleId::from_aid("1bK_4101").unwrap().to_filename(), "M.1700000001.A.001");
+        assert_eq!(id.get_time(), UNIX_EPOCH + Duration::from_secs(1700000001));
+
+        let digest = ArticleId::from_filename("G.1234567890.A.FFF").unwrap();
+        assert_eq!(ArticleId::from_aid(&digest.to_aid()), Some(digest));
+    }
+
+    #[test]
+    fn invalid_ids() {
+        assert_eq!(ArticleId::from_aid("#1bK_410"), None);
+        assert_eq!(ArticleId::from_aid("#1bK_41.1"), None);
+        assert_eq!(ArticleId::from_aid("#zzzzzzzz"), None);
+        assert_eq!(ArticleId::from_filename("M.1700000001.A"), None);
+        assert_eq!(ArticleId::from_filename("X.1700000001.A.001"), None);
+        assert_eq!(ArticleId::from_filename("M.1700000001.A.1001"), None);
+    }
+
+    #[test]
+    fn urls() {
+        let id = ArticleId::from_aid("#1bK_4101").unwrap();
+        let url = "https://www.ptt.cc/bbs/Gossiping/M.1700000001.A.001.html";
+        assert_eq!(id.to_url("Gossiping"), url);
+        assert_eq!(parse_url(url), Some(("Gossiping".to_string(), id)));
+        assert_eq!(parse_url("https://www.ptt.cc/bbs/Gossiping/index.html"), None);
+    }
+
+    #[test]
+    fn article_info_screen() {
+        let mut screen = Screen::new();
+        screen.process(b"\x1b[6;1H");
+        screen.process(&Big5.encode("│ 文章代碼(AID): #1bK_4101 (Gossiping) [ptt.cc] [問卦] 測試").unwrap());
+        assert_eq!(parse_article_info(&screen), ArticleId::from_filename("M.1700000001.A.001"));
+
+        let mut screen = Screen::new();
+        screen.process(b"\x1b[7;1H");
+        screen.process(&Big5.encode("│ 文章網址: https://www.ptt.cc/bbs/Test/M.1700000002.A.002.html").unwrap());
+        assert_eq!(parse_article_info(&screen), ArticleId::from_filename("M.1700000002.A.002"));
+    }
+}
@@ -0,0 +1,302 @@
+use crate::aid::{self, ArticleId};
+use crate::comment::{self, Comment};
+use crate::screen::Screen;
+
+// 作者、標題、時間與分隔線
+const HEADER_ROWS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ArticleHeader {
+    pub author: String,
+    pub board: String,
+    pub title: String,
+    pub time: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Article {
+    pub header: ArticleHeader,
+    pub body_lines: Vec<String>,
+    pub pushes: Vec<Comment>,
+    pub id: Option<ArticleId> // 從「※ 文章網址:」那一行取出來
+}
+
+// 狀態列："瀏覽 第 1/3 頁 ( 25%)  目前顯示: 第 01~22 行"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrowseStatus {
+    pub page: usize,
+    pub total_pages: usize,
+    pub percent: usize,
+    pub first_line: usize,
+    pub last_line: usize
+}
+
+impl BrowseStatus {
+    pub fn parse(status_line: &str) -> Option<BrowseStatus> {
+        if !status_line.contains("瀏覽") {
+            return None;
+        }
+
+        // 依序取出狀態列中的數字
+        let numbers: Vec<usize> = status_line
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| s.parse().ok())
+            .collect();
+        if numbers.len() < 5 {
+            return None;
+        }
+
+        Some(BrowseStatus {
+            page: numbers[0],
+            total_pages: numbers[1],
+            percent: numbers[2],
+            first_line: numbers[3],
+            last_line: numbers[4]
+        })
+    }
+
+    pub fn is_last_page(&self) -> bool {
+        self.percent >= 100
+    }
+}
+
+// 把每一頁的畫面依照狀態列的行號拼回完整的文章
+pub struct ArticleReader {
+    lines: Vec<Option<String>>
+}
+
+impl Default for ArticleReader {
+    fn default() -> ArticleReader {
+        ArticleReader::new()
+    }
+}
+
+impl ArticleReader {
+    pub fn new() -> ArticleReader {
+        ArticleReader {
+            lines: vec![]
+        }
+    }
+
+    // 回傳這一頁的狀態，若畫面不是文章瀏覽畫面則回傳 None
+    pub fn add_page(&mut self, screen: &Screen) -> Option<BrowseStatus> {
+        // 最後一行是狀態列，其餘都是文章內容
+        let content_rows = screen.height() - 1;
+        let status = BrowseStatus::parse(&screen.row_text(content_rows))?;
+        if status.first_line == 0 || status.last_line < status.first_line {
+            return None;
+        }
+
+        let count = status.last_line - status.first_line + 1;
+        if self.lines.len() < status.last_line {
+            self.lines.resize(status.last_line, None);
+        }
+        for rid in 0 .. count.min(content_rows) {
+            let line_no = status.first_line - 1 + rid;
+            if self.lines[line_no].is_none() {
+                self.lines[line_no] = Some(screen.row_text(rid).trim_end().to_string());
+            }
+        }
+
+        Some(status)
+    }
+
+    pub fn finish(self) -> Article {
+        let lines: Vec<String> = self.lines.into_iter().map(|l| l.unwrap_or_default()).collect();
+        build_article(lines)
+    }
+}
+
+fn build_article(lines: Vec<String>) -> Article {
+    let mut header = ArticleHeader::default();
+    let mut body_start = 0;
+
+    for (i, line) in lines.iter().enumerate().take(HEADER_ROWS) {
+        let text = line.trim_start();
+        if let Some(rest) = text.strip_prefix("作者") {
+            match rest.find("看板") {
+                Some(pos) => {
+                    header.author = rest[.. pos].trim().to_string();
+                    header.board = rest[pos + "看板".len() ..].trim().to_string();
+                },
+                None => header.author = rest.trim().to_string()
+            }
+        } else if let Some(rest) = text.strip_prefix("標題") {
+            header.title = rest.trim().to_string();
+        } else if let Some(rest) = text.strip_prefix("時間") {
+            header.time = rest.trim().to_string();
+        } else if text.starts_with('─') {
+            body_start = i + 1;
+            break;
+        }
+    }
+
+    // 推文從「發信站」那一行之後開始
+    let push_start = lines.iter()
+        .position(|l| l.starts_with("※ 發信站"))
+        .map(|i| i + 1)
+        .unwrap_or(lines.len());
+
+    let mut id = None;
+    let mut body_lines = vec![];
+    let mut push_lines: Vec<String> = vec![];
+    let mut wrapped = false; // 上一行是被折行、還沒出現時間的推文
+    for (i, line) in lines.into_iter().enumerate().skip(body_start) {
+        if let Some(url) = line.strip_prefix("※ 文章網址:") {
+            if let Some((_, article_id)) = aid::parse_url(url) {
+                id = Some(article_id);
+                continue;
+            }
+        }
+        if i < push_start {
+            body_lines.push(line);
+        } else if comment::is_comment_line(&line) {
+            wrapped = !comment::is_complete_comment_line(&line);
+            push_lines.push(line);
+        } else if wrapped && comment::is_complete_comment_line(&line) {
+            // 上一則推文被折行了，折下來的這行結尾是時間，把它接回去
+            if let Some(last) = push_lines.last_mut() {
+                last.push_str(&line);
+            }
+            wrapped = false;
+        } else {
+            // 沒有時間的推文不會一直吃掉後面的內容
+            body_lines.push(line);
+            wrapped = false;
+        }
+    }
+
+    Article {
+        header,
+        body_lines,
+        pushes: push_lines.iter().filter_map(|l| comment::parse_comment(l)).collect(),
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArticleReader, BrowseStatus};
+    use crate::screen::{Big5, Screen, ScreenEncoding};
+
+    // 一頁文章：第 first 行開始的 rows 行內容，加上狀態列
+    fn page(lines: &[String], first: usize, rows: usize, page: usize, total_pages: usize) -> Screen {
+        let last = (first + rows - 1).min(lines.len());
+        let percent = last * 100 / lines.len();
+        let mut screen = Screen::new();
+        for (rid, line) in lines[first - 1 .. last].iter().enumerate() {
+            screen.process(format!("\x1b[{};1H", rid + 1).as_bytes());
+            screen.process(&Big5.encode(line).unwrap());
+        }
+        let status = format!("  瀏覽 第 {}/{} 頁 ({:>3}%)  目前顯示: 第 {:02}~{:02} 行  (y)回應(X%)推文(h)說明(←)離開 ",
+            page, total_pages, percent, first, last);
+        screen.process(b"\x1b[24;1H");
+        screen.process(&Big5.encode(&status).unwrap());
+        screen
+    }
+
+    fn article_lines(body: usize) -> Vec<String> {
+        let mut lines = vec![
+            " 作者  writer (寫手)                                         看板  Test".to_string(),
+            " 標題  [心得] 測試".to_string(),
+            " 時間  Sun Aug  3 12:00:00 2025".to_string(),
+            "─".repeat(39)
+        ];
+        lines.extend((1 ..= body).map(|n| format!("第 {} 行", n)));
+        lines
+    }
+
+    #[test]
+    fn status_line() {
+        let status = BrowseStatus::parse("  瀏覽 第 2/3 頁 ( 66%)  目前顯示: 第 24~46 行  (y)回應(X%)推文(h)說明(←)離開 ").unwrap();
+        assert_eq!((status.page, status.total_pages, status.percent), (2, 3, 66));
+        assert_eq!((status.first_line, status.last_line), (24, 46));
+        assert!(!status.is_last_page());
+
+        let last = BrowseStatus::parse("  瀏覽 第 3/3 頁 (100%)  目前顯示: 第 45~67 行  (←)離開 ").unwrap();
+        assert!(last.is_last_page());
+
+        assert_eq!(BrowseStatus::parse(" 文章選讀  (y)回應(X)推文"), None);
+        assert_eq!(BrowseStatus::parse("  瀏覽 第 1/1 頁"), None);
+    }
+
+    #[test]
+    fn single_page_article() {
+        let lines = article_lines(3);
+        let mut reader = ArticleReader::new();
+        let status = reader.add_page(&page(&lines, 1, 23, 1, 1)).unwrap();
+        assert!(status.is_last_page());
+        assert_eq!((status.first_line, status.last_line), (1, 7));
+
+        let article = reader.finish();
+        assert_eq!(article.header.author, "writer (寫手)");
+        assert_eq!(article.header.board, "Test");
+        assert_eq!(article.header.title, "[心得] 測試");
+        assert_eq!(article.header.time, "Sun Aug  3 12:00:00 2025");
+        assert_eq!(article.body_lines, vec!["第 1 行", "第 2 行", "第 3 行"]);
+    }
+
+    #[test]
+    fn overlapping_pages_are_stitched_in_order() {
+        // 最後一頁會往回捲，跟前一頁重疊
+        let lines = article_lines(26);
+        let mut reader = ArticleReader::new();
+        assert!(!reader.add_page(&page(&lines, 1, 23, 1, 2)).unwrap().is_last_page());
+        assert!(reader.add_page(&page(&lines, 8, 23, 2, 2)).unwrap().is_last_page());
+
+        let body: Vec<String> = (1 ..= 26).map(|n| format!("第 {} 行", n)).collect();
+        assert_eq!(reader.finish().body_lines, body);
+    }
+
+    #[test]
+    fn other_screens_are_not_pages() {
+        let mut screen = Screen::new();
+        screen.process(b"\x1b[24;1H");
+        screen.process(&Big5.encode(" 文章選讀  (y)回應(X)推文").unwrap());
+        assert_eq!(ArticleReader::new().add_page(&screen), None);
+    }
+
+    #[test]
+    fn wrapped_comments_are_joined() {
+        let mut lines = article_lines(1);
+        lines.extend([
+            "--".to_string(),
+            "※ 發信站: 批踢踢實業坊(ptt.cc), 來自: 1.2.3.4 (臺灣)".to_string(),
+            "推 alice: 這是一則很長很長的推文".to_string(),
+            "折下來的後半段                                              08/03 12:01".to_string(),
+            "→ bob: 沒有時間的推文".to_string(),
+            "一般的內文".to_string(),
+            "最後一行 08/03".to_string(),
+            "噓 carol: 不推                                              08/03 12:02".to_string()
+        ]);
+        let mut reader = ArticleReader::new();
+        reader.add_page(&page(&lines, 1, 23, 1, 1)).unwrap();
+
+        let article = reader.finish();
+        let contents: Vec<&str> = article.pushes.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(contents, vec!["這是一則很長很長的推文折下來的後半段", "沒有時間的推文", "不推"]);
+        assert_eq!(article.pushes[0].timestamp, "08/03 12:01");
+        assert_eq!(article.pushes[1].timestamp, "");
+        assert!(article.body_lines.iter().any(|line| line == "一般的內文"));
+        assert!(article.body_lines.iter().any(|line| line == "最後一行 08/03"));
+    }
+
+    #[test]
+    fn missing_header_fields_are_left_empty() {
+        let lines: Vec<String> = vec![
+            " 作者  writer".to_string(),
+            " 標題  沒有時間的文章".to_string(),
+            "─".repeat(39),
+            "內文".to_string()
+        ];
+        let mut reader = ArticleReader::new();
+        reader.add_page(&page(&lines, 1, 23, 1, 1)).unwrap();
+
+        let article = reader.finish();
+        assert_eq!(article.header.author, "writer");
+        assert_eq!(article.header.board, "");
+        assert_eq!(article.header.time, "");
+        assert_eq!(article.body_lines, vec!["內文"]);
+    }
+}
@@ -0,0 +1,297 @@
+use std::fmt;
+
+use crate::aid::ArticleId;
+use crate::screen::{Screen, ScreenEncoding};
+
+// 文章列表從第 4 行開始，一直到狀態列的前一行
+pub const LIST_FIRST_ROW: usize = 3;
+
+// 每一行的欄位位置 (以 byte 計)：
+// "●  1234 + 5 8/03 author       □ [分類] 標題"
+//  ^      ^^ ^     ^             ^
+//  0      78 10    16            29
+const COL_INDEX: (usize, usize) = (0, 7);
+const COL_FLAG: usize = 7;
+const COL_PUSH: (usize, usize) = (8, 10);
+const COL_DATE: (usize, usize) = (10, 16);
+const COL_AUTHOR: (usize, usize) = (16, 29);
+const COL_TITLE: usize = 29;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushCount {
+    Count(u8),    // 1 ~ 99
+    Explosion,    // 爆：100 以上
+    Boo(u8),      // X1 ~ X9：-10 ~ -99 (存十位數)
+    BooExplosion  // XX：-100 以下
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArticleListEntry {
+    pub index: Option<u32>, // 置底文章沒有編號
+    pub push_count: Option<PushCount>,
+    pub flag: Option<char>,
+    pub date: String,
+    pub author: String,
+    pub title: String,
+    pub category: Option<String>,
+    pub deleted: bool,
+    pub board: String,        // 從畫面第一行的「看板《...》」取出，只解析一行時是空的
+    pub id: Option<ArticleId> // 列表上看不到，要按 Q 才查得到 (見 PttConnection::fill_article_ids)
+}
+
+impl PushCount {
+    // 換算成推文數，噓文是負的 (X1 當作 -10、XX 當作 -100)
+    pub fn get_score(&self) -> i32 {
+        match *self {
+            PushCount::Count(n) => n as i32,
+            PushCount::Explosion => 100,
+            PushCount::Boo(n) => -10 * n as i32,
+            PushCount::BooExplosion => -100
+        }
+    }
+}
+
+impl ArticleListEntry {
+    // 網頁版的網址，要先查到文章代碼
+    pub fn to_url(&self) -> Option<String> {
+        match self.id {
+            Some(ref id) if !self.board.is_empty() => Some(id.to_url(&self.board)),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for PushCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PushCount::Count(n) => write!(f, "{}", n),
+            PushCount::Explosion => write!(f, "爆"),
+            PushCount::Boo(n) => write!(f, "X{}", n),
+            PushCount::BooExplosion => write!(f, "XX")
+        }
+    }
+}
+
+impl fmt::Display for ArticleListEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "{:>7}", index)?,
+            None => write!(f, "{:>7}", "★")?
+        }
+        let push = match self.push_count {
+            Some(ref count) => count.to_string(),
+            None => String::new()
+        };
+        write!(f, " {}{:>2} {:>5} {:<12} {}", self.flag.unwrap_or(' '), push,
+            self.date, self.author, self.title)
+    }
+}
+
+// 畫面上第 3 行到倒數第 2 行的文章，不是文章的行會被略過
+pub fn parse_article_list(screen: &Screen) -> Vec<ArticleListEntry> {
+    let board = parse_board_name(screen).unwrap_or_default();
+    let mut entries = vec![];
+    for rid in LIST_FIRST_ROW .. screen.height().saturating_sub(1) {
+        if let Some(mut entry) = parse_article_list_row(&screen.row_bytes(rid), screen.get_encoding()) {
+            entry.board = board.clone();
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+// 游標 (● 或 >) 所在的那一篇
+pub fn parse_selected_article(screen: &Screen) -> Option<ArticleListEntry> {
+    for rid in LIST_FIRST_ROW .. screen.height().saturating_sub(1) {
+        let row = screen.row_bytes(rid);
+        if row.len() >= 2 && screen.get_encoding().decode(&row[.. 2]).starts_with(['>', '●']) {
+            return parse_article_list_row(&row, screen.get_encoding()).map(|mut entry| {
+                entry.board = parse_board_name(screen).unwrap_or_default();
+                entry
+            });
+        }
+    }
+    None
+}
+
+// 第一行："【板主:sysop】   八卦板   看板《Gossiping》"
+pub fn parse_board_name(screen: &Screen) -> Option<String> {
+    let header = screen.row_text(0);
+    let start = header.find("看板《")? + "看板《".len();
+    let end = header[start ..].find('》')?;
+    Some(header[start .. start + end].to_string())
+}
+
+pub fn parse_article_list_row(row: &[u8], encoding: &dyn ScreenEncoding) -> Option<ArticleListEntry> {
+    // 畫面太窄或這一行還沒畫完
+    if row.len() < COL_TITLE {
+        return None;
+    }
+
+    // 游標 (● 或 >) 可能蓋在編號前面
+    let index_text = encoding.decode(&row[COL_INDEX.0 .. COL_INDEX.1]);
+    let index_text = index_text.trim_start_matches(['●', '>']).trim();
+    let index = if index_text == "★" {
+        None
+    } else {
+        match index_text.parse::<u32>() {
+            Ok(n) => Some(n),
+            Err(_) => return None // 不是文章列
+        }
+    };
+
+    let flag = match row[COL_FLAG] {
+        b' ' => None,
+        b => Some(b as char)
+    };
+
+    let date = encoding.decode(&row[COL_DATE.0 .. COL_DATE.1]).trim().to_string();
+    let author = encoding.decode(&row[COL_AUTHOR.0 .. COL_AUTHOR.1]).trim().to_string();
+
+    let title = encoding.decode(&row[COL_TITLE ..]);
+    let title = title.trim_start_matches('□').trim().to_string();
+    let deleted = title.starts_with('(') && title.contains("已被") && title.contains("刪除");
+
+    Some(ArticleListEntry {
+        index,
+        push_count: parse_push_count(&encoding.decode(&row[COL_PUSH.0 .. COL_PUSH.1])),
+        flag,
+        date,
+        author,
+        category: parse_category(&title),
+        title,
+        deleted,
+        board: String::new(),
+        id: None
+    })
+}
+
+fn parse_push_count(text: &str) -> Option<PushCount> {
+    let text = text.trim();
+    match text {
+        "" => None,
+        "爆" => Some(PushCount::Explosion),
+        "XX" => Some(PushCount::BooExplosion),
+        _ if text.starts_with('X') => text[1 ..].parse().ok().map(PushCount::Boo),
+        _ => text.parse().ok().map(PushCount::Count)
+    }
+}
+
+fn parse_category(title: &str) -> Option<String> {
+    let title = title.trim_start_matches("R:").trim_start_matches('轉').trim_start();
+    if !title.starts_with('[') {
+        return None;
+    }
+    title.find(']').map(|end| title[1 .. end].trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_article_list, parse_article_list_row, ArticleListEntry, PushCount};
+    use crate::aid::ArticleId;
+    use crate::screen::{Big5, Screen, ScreenEncoding};
+
+    // 依照欄位的寬度 (byte) 組出一行，編號、推文數與日期靠右，作者靠左
+    fn row(index: &str, flag: char, push: &str, date: &str, author: &str, title: &str) -> Vec<u8> {
+        let field = |text: &str, width: usize, right: bool| {
+            let bytes = Big5.encode(text).unwrap();
+            assert!(bytes.len() <= width);
+            let padding = vec![b' '; width - bytes.len()];
+            if right { [padding, bytes].concat() } else { [bytes, padding].concat() }
+        };
+        let mut row = field(index, 7, true);
+        row.push(flag as u8);
+        row.extend(field(push, 2, true));
+        row.extend(field(date, 6, true));
+        row.extend(field(author, 13, false));
+        row.extend(Big5.encode(title).unwrap());
+        row
+    }
+
+    fn parse(row: &[u8]) -> ArticleListEntry {
+        parse_article_list_row(row, &Big5).unwrap()
+    }
+
+    #[test]
+    fn push_counts() {
+        let cases = [
+            ("", None),
+            ("5", Some(PushCount::Count(5))),
+            ("99", Some(PushCount::Count(99))),
+            ("爆", Some(PushCount::Explosion)),
+            ("X1", Some(PushCount::Boo(1))),
+            ("XX", Some(PushCount::BooExplosion))
+        ];
+        for (push, expected) in cases {
+            let entry = parse(&row("1234", ' ', push, "8/03", "author", "□ [問卦] 標題"));
+            assert_eq!(entry.push_count, expected, "{}", push);
+        }
+    }
+
+    #[test]
+    fn a_normal_row() {
+        let entry = parse(&row("1234", '+', "5", "8/03", "author", "□ [問卦] 八卦板的標題"));
+        assert_eq!(entry.index, Some(1234));
+        assert_eq!(entry.flag, Some('+'));
+        assert_eq!(entry.date, "8/03");
+        assert_eq!(entry.author, "author");
+        assert_eq!(entry.title, "[問卦] 八卦板的標題");
+        assert_eq!(entry.category, Some("問卦".to_string()));
+        assert!(!entry.deleted);
+        assert_eq!(entry.id, None);
+    }
+
+    #[test]
+    fn read_and_unread_flags() {
+        assert_eq!(parse(&row("1", ' ', "", "8/03", "a", "□ 已讀")).flag, None);
+        assert_eq!(parse(&row("1", '+', "", "8/03", "a", "□ 未讀")).flag, Some('+'));
+        assert_eq!(parse(&row("1", 'M', "", "8/03", "a", "□ 被標記")).flag, Some('M'));
+        assert_eq!(parse(&row("1", '~', "", "8/03", "a", "□ 有新推文")).flag, Some('~'));
+    }
+
+    #[test]
+    fn pinned_rows_and_cursors() {
+        let pinned = parse(&row("★", ' ', "", "8/03", "SYSOP", "□ [公告] 板規"));
+        assert_eq!(pinned.index, None);
+        assert_eq!(pinned.author, "SYSOP");
+
+        assert_eq!(parse(&row(">  1234", ' ', "", "8/03", "a", "□ t")).index, Some(1234));
+        assert_eq!(parse(&row("● 1234", ' ', "", "8/03", "a", "□ t")).index, Some(1234));
+        assert_eq!(parse(&row(">    ★", ' ', "", "8/03", "a", "□ t")).index, None);
+    }
+
+    #[test]
+    fn deleted_rows() {
+        let entry = parse(&row("1234", ' ', "", "8/03", "-", "□ (本文已被刪除) [author]"));
+        assert!(entry.deleted);
+        assert_eq!(entry.category, None);
+
+        let entry = parse(&row("1234", ' ', "", "8/03", "-", "(已被SYSOP刪除) <author> 違反板規"));
+        assert!(entry.deleted);
+    }
+
+    #[test]
+    fn other_and_short_rows_are_skipped() {
+        assert_eq!(parse_article_list_row(&Big5.encode("   編號    日 期 作  者       文  章  標  題").unwrap(), &Big5), None);
+        assert_eq!(parse_article_list_row(&row("1234", ' ', "", "8/03", "author", "")[.. 20], &Big5), None);
+        assert_eq!(parse_article_list_row(b"", &Big5), None);
+        assert!(parse_article_list_row(&row("1234", ' ', "", "8/03", "author", ""), &Big5).is_some());
+    }
+
+    #[test]
+    fn entries_know_their_board() {
+        let mut screen = Screen::new();
+        screen.process(&Big5.encode("【板主:sysop】   八卦板   看板《Gossiping》").unwrap());
+        screen.process(b"\x1b[4;1H");
+        screen.process(&row("1234", '+', "5", "8/03", "author", "□ [問卦] 標題"));
+
+        let mut entry = parse_article_list(&screen).remove(0);
+        assert_eq!(entry.board, "Gossiping");
+        assert_eq!(entry.to_url(), None);
+        entry.id = ArticleId::from_filename("M.1700000000.A.000");
+        assert_eq!(entry.to_url().unwrap(), "https://www.ptt.cc/bbs/Gossiping/M.1700000000.A.000.html");
+
+        // 只解析一行時不知道是哪個看板
+        assert_eq!(parse(&row("1234", '+', "5", "8/03", "author", "標題")).board, "");
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentKind {
+    Push,  // 推
+    Boo,   // 噓
+    Arrow  // →
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub kind: CommentKind,
+    pub author: String,
+    pub content: String,
+    pub ip: Option<String>,
+    pub timestamp: String // "MM/DD HH:MM"，舊文章可能只有 "MM/DD"
+}
+
+impl fmt::Display for Comment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tag = match self.kind {
+            CommentKind::Push => "推",
+            CommentKind::Boo => "噓",
+            CommentKind::Arrow => "→"
+        };
+        write!(f, "{} {}: {}", tag, self.author, self.content)?;
+        if let Some(ref ip) = self.ip {
+            write!(f, " {}", ip)?;
+        }
+        write!(f, " {}", self.timestamp)
+    }
+}
+
+pub fn is_comment_line(line: &str) -> bool {
+    comment_kind(line).is_some()
+}
+
+// 推文太長時會被折到下一行，時間會出現在第二行的結尾
+pub fn is_complete_comment_line(line: &str) -> bool {
+    split_timestamp(line.trim_end()).is_some()
+}
+
+// 推文格式："推 author: content          [ip] MM/DD HH:MM"
+pub fn parse_comment(line: &str) -> Option<Comment> {
+    let kind = comment_kind(line)?;
+    // 跳過 "推 " 這兩個字
+    let rest: String = line.chars().skip(2).collect();
+    let colon = rest.find(':')?;
+    let author = rest[.. colon].trim().to_string();
+    let rest = rest[colon + 1 ..].trim_end();
+
+    let (rest, timestamp) = match split_timestamp(rest) {
+        Some((rest, timestamp)) => (rest, timestamp.to_string()),
+        None => (rest, String::new())
+    };
+    let (rest, ip) = match rsplit_token(rest) {
+        Some((before, token)) if is_ip(token) => (before, Some(token.to_string())),
+        _ => (rest, None)
+    };
+
+    Some(Comment {
+        kind,
+        author,
+        content: rest.trim().to_string(),
+        ip,
+        timestamp
+    })
+}
+
+fn comment_kind(line: &str) -> Option<CommentKind> {
+    if line.starts_with("推 ") {
+        Some(CommentKind::Push)
+    } else if line.starts_with("噓 ") {
+        Some(CommentKind::Boo)
+    } else if line.starts_with("→ ") {
+        Some(CommentKind::Arrow)
+    } else {
+        None
+    }
+}
+
+// 回傳 (時間之前的內容, 時間)
+fn split_timestamp(text: &str) -> Option<(&str, &str)> {
+    let (before, last) = rsplit_token(text)?;
+    if is_time(last) {
+        let (before_date, date) = rsplit_token(before)?;
+        if is_date(date) {
+            return Some((before_date, text[before_date.len() ..].trim()));
+        }
+        None
+    } else if is_date(last) {
+        Some((before, last))
+    } else {
+        None
+    }
+}
+
+fn rsplit_token(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_end();
+    let pos = text.rfind(' ')?;
+    Some((text[.. pos].trim_end(), &text[pos + 1 ..]))
+}
+
+fn is_digits_pair(text: &str, sep: char) -> bool {
+    let mut parts = text.split(sep);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(a), Some(b), None) => a.len() == 2 && b.len() == 2
+            && a.chars().all(|c| c.is_ascii_digit()) && b.chars().all(|c| c.is_ascii_digit()),
+        _ => false
+    }
+}
+
+fn is_date(text: &str) -> bool {
+    is_digits_pair(text, '/')
+}
+
+fn is_time(text: &str) -> bool {
+    is_digits_pair(text, ':')
+}
+
+fn is_ip(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('.').collect();
+    parts.len() == 4 && parts.iter().all(|p| !p.is_empty() && p.parse::<u8>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_complete_comment_line, parse_comment, CommentKind};
+
+    #[test]
+    fn comment_forms() {
+        // (原始的一行, 種類, 作者, 內容, IP, 時間)
+        let cases = [
+            ("推 alice: 推推                                            08/03 12:01",
+                CommentKind::Push, "alice", "推推", None, "08/03 12:01"),
+            ("噓 bob: 不推                                              08/03 12:02",
+                CommentKind::Boo, "bob", "不推", None, "08/03 12:02"),
+            ("→ carol: 補充一下                                        08/03 12:03",
+                CommentKind::Arrow, "carol", "補充一下", None, "08/03 12:03"),
+            ("推 dave: 有 IP 的推文                    1.160.22.33 08/03 12:04",
+                CommentKind::Push, "dave", "有 IP 的推文", Some("1.160.22.33"), "08/03 12:04"),
+            ("推 erin: 舊文章只有日期                                   08/03",
+                CommentKind::Push, "erin", "舊文章只有日期", None, "08/03"),
+            ("→ frank: 時間: 12:00 開始",
+                CommentKind::Arrow, "frank", "時間: 12:00 開始", None, ""),
+            ("推 grace: 1.2.3.4 在內文裡不算 IP 嗎                      08/03 12:05",
+                CommentKind::Push, "grace", "1.2.3.4 在內文裡不算 IP 嗎", None, "08/03 12:05")
+        ];
+        for (line, kind, author, content, ip, timestamp) in cases {
+            let comment = parse_comment(line).unwrap();
+            assert_eq!(comment.kind, kind, "{}", line);
+            assert_eq!(comment.author, author, "{}", line);
+            assert_eq!(comment.content, content, "{}", line);
+            assert_eq!(comment.ip.as_deref(), ip, "{}", line);
+            assert_eq!(comment.timestamp, timestamp, "{}", line);
+        }
+    }
+
+    #[test]
+    fn other_lines_are_not_comments() {
+        assert_eq!(parse_comment("推文在下面"), None);
+        assert_eq!(parse_comment("※ 發信站: 批踢踢實業坊(ptt.cc)"), None);
+        assert_eq!(parse_comment("推 沒有冒號"), None);
+    }
+
+    #[test]
+    fn wrapped_comment_lines() {
+        assert!(is_complete_comment_line("推 alice: 推推    08/03 12:01"));
+        assert!(is_complete_comment_line("推 alice: 推推    08/03"));
+        assert!(!is_complete_comment_line("推 alice: 這一行被折到下一行"));
+        assert!(is_complete_comment_line("折下來的後半段    08/03 12:01"));
+    }
+}
@@ -0,0 +1,251 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::CrawlerError;
+
+const DEFAULT_HOST: &str = "ptt.cc";
+const DEFAULT_PORT: u16 = 23;
+const DEFAULT_SSH_PORT: u16 = 22;
+const DEFAULT_BUFFER_SIZE: usize = 256;
+const DEFAULT_WAIT_TIMEOUT_SECS: u64 = 3;
+const DEFAULT_OPERATION_DEADLINE_SECS: u64 = 60;
+const DEFAULT_QUIET_PERIOD_MILLIS: u64 = 100;
+const DEFAULT_TERMINAL_SIZE: (usize, usize) = (80, 24);
+// 文章列表等畫面是依照 80x24 的固定欄位解析的，不能再小
+const MIN_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransportKind {
+    Telnet,
+    WebSocket(String), // URL，例如 wss://ws.ptt.cc/bbs
+    Ssh(String)        // SSH 使用者名稱，例如 bbsu
+}
+
+// 連線設定，例如：
+// ConnectionConfig::new().host("ptt2.cc").wait_timeout(Duration::from_secs(5))
+#[derive(Debug, Clone)]
+pub struct ConnectionConfig {
+    transport: TransportKind,
+    host: String,
+    port: u16,
+    buffer_size: usize,
+    wait_timeout: Duration,
+    quiet_period: Duration,
+    key_interval: Duration,
+    operation_deadline: Duration,
+    terminal_size: (usize, usize), // (寬, 高)
+    record_path: Option<PathBuf>,
+    kick_duplicate_login: bool,
+    ssh_host_keys: Vec<String> // 信任的 SSH 主機金鑰指紋
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> ConnectionConfig {
+        ConnectionConfig::new()
+    }
+}
+
+impl ConnectionConfig {
+    pub fn new() -> ConnectionConfig {
+        ConnectionConfig {
+            transport: TransportKind::Telnet,
+            host: DEFAULT_HOST.to_string(),
+            port: DEFAULT_PORT,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            wait_timeout: Duration::from_secs(DEFAULT_WAIT_TIMEOUT_SECS),
+            quiet_period: Duration::from_millis(DEFAULT_QUIET_PERIOD_MILLIS),
+            key_interval: Duration::ZERO,
+            operation_deadline: Duration::from_secs(DEFAULT_OPERATION_DEADLINE_SECS),
+            terminal_size: DEFAULT_TERMINAL_SIZE,
+            record_path: None,
+            kick_duplicate_login: true,
+            ssh_host_keys: vec![]
+        }
+    }
+
+    // 改用 WebSocket 連線，此時 host 與 port 不會被使用
+    pub fn websocket(mut self, url: &str) -> ConnectionConfig {
+        self.transport = TransportKind::WebSocket(url.to_string());
+        self
+    }
+
+    // 改用 SSH 連線，並把 port 設成 22 (之後仍可用 port() 修改)
+    // 還要用 ssh_host_key() 指定主機金鑰，否則連線會被拒絕
+    pub fn ssh(mut self, user: &str) -> ConnectionConfig {
+        self.transport = TransportKind::Ssh(user.to_string());
+        self.port = DEFAULT_SSH_PORT;
+        self
+    }
+
+    // 信任的主機金鑰指紋，格式跟 ssh-keygen -l 一樣，例如 "SHA256:Jx3n..."
+    // 可以呼叫多次加入不同種類的金鑰，連線時金鑰不符就會失敗
+    pub fn ssh_host_key(mut self, fingerprint: &str) -> ConnectionConfig {
+        self.ssh_host_keys.push(fingerprint.trim().to_string());
+        self
+    }
+
+    pub fn host(mut self, host: &str) -> ConnectionConfig {
+        self.host = host.to_string();
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> ConnectionConfig {
+        self.port = port;
+        self
+    }
+
+    // 每次從連線讀取資料時使用的 buffer 大小
+    pub fn buffer_size(mut self, size: usize) -> ConnectionConfig {
+        self.buffer_size = size;
+        self
+    }
+
+    // 等待下一筆資料的時間，超過就視為畫面已經不會再更新
+    pub fn wait_timeout(mut self, timeout: Duration) -> ConnectionConfig {
+        self.wait_timeout = timeout;
+        self
+    }
+
+    // 伺服器開始回應之後，多久沒有新資料就當作畫面畫完了 (下限，實際值會依連線狀況拉長)
+    pub fn quiet_period(mut self, period: Duration) -> ConnectionConfig {
+        self.quiet_period = period;
+        self
+    }
+
+    // send_keys 每個按鍵之間的間隔，預設不等待
+    pub fn key_interval(mut self, interval: Duration) -> ConnectionConfig {
+        self.key_interval = interval;
+        self
+    }
+
+    // 單一操作 (例如等待某個字串出現) 最多花費的時間
+    pub fn operation_deadline(mut self, deadline: Duration) -> ConnectionConfig {
+        self.operation_deadline = deadline;
+        self
+    }
+
+    // 透過 telnet NAWS 或 SSH PTY 告訴伺服器的終端機大小，畫面也會用這個大小模擬
+    // 不能小於 80x24，連線時會用 validate() 檢查
+    pub fn terminal_size(mut self, width: usize, height: usize) -> ConnectionConfig {
+        self.terminal_size = (width, height);
+        self
+    }
+
+    // 把收送的資料錄進 session 檔，之後可以用 ReplayTransport 重播
+    pub fn record<P: AsRef<Path>>(mut self, path: P) -> ConnectionConfig {
+        self.record_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    // 登入時遇到「您想刪除其他重複登入的連線嗎」要不要踢掉其他連線
+    pub fn kick_duplicate_login(mut self, kick: bool) -> ConnectionConfig {
+        self.kick_duplicate_login = kick;
+        self
+    }
+
+    // 連線前檢查設定是否可用
+    pub fn validate(&self) -> Result<(), CrawlerError> {
+        let (width, height) = self.terminal_size;
+        if width < MIN_TERMINAL_SIZE.0 || height < MIN_TERMINAL_SIZE.1 {
+            return Err(CrawlerError::InvalidConfig(format!("終端機大小 {}x{} 小於 {}x{}",
+                width, height, MIN_TERMINAL_SIZE.0, MIN_TERMINAL_SIZE.1)));
+        }
+        // transport 不接受 0 的讀取逾時
+        let durations = [
+            ("wait_timeout", self.wait_timeout),
+            ("quiet_period", self.quiet_period),
+            ("operation_deadline", self.operation_deadline)
+        ];
+        for (name, duration) in durations {
+            if duration.is_zero() {
+                return Err(CrawlerError::InvalidConfig(format!("{} 不能是 0", name)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_transport(&self) -> &TransportKind {
+        &self.transport
+    }
+
+    pub fn get_host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn get_port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn get_buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    pub fn get_wait_timeout(&self) -> Duration {
+        self.wait_timeout
+    }
+
+    pub fn get_quiet_period(&self) -> Duration {
+        self.quiet_period
+    }
+
+    pub fn get_key_interval(&self) -> Duration {
+        self.key_interval
+    }
+
+    pub fn get_operation_deadline(&self) -> Duration {
+        self.operation_deadline
+    }
+
+    pub fn get_terminal_size(&self) -> (usize, usize) {
+        self.terminal_size
+    }
+
+    pub fn get_record_path(&self) -> Option<&Path> {
+        self.record_path.as_deref()
+    }
+
+    pub fn get_kick_duplicate_login(&self) -> bool {
+        self.kick_duplicate_login
+    }
+
+    pub fn get_ssh_host_keys(&self) -> &[String] {
+        &self.ssh_host_keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ConnectionConfig;
+    use crate::error::CrawlerError;
+
+    #[test]
+    fn terminal_size_must_fit_the_parsers() {
+        assert!(ConnectionConfig::new().validate().is_ok());
+        assert!(ConnectionConfig::new().terminal_size(132, 50).validate().is_ok());
+        for (width, height) in [(0, 0), (80, 0), (79, 24), (80, 23), (29, 24)] {
+            match ConnectionConfig::new().terminal_size(width, height).validate() {
+                Err(CrawlerError::InvalidConfig(_)) => {},
+                other => panic!("{}x{}: {:?}", width, height, other)
+            }
+        }
+    }
+
+    #[test]
+    fn durations_must_not_be_zero() {
+        let configs = [
+            ConnectionConfig::new().wait_timeout(Duration::ZERO),
+            ConnectionConfig::new().quiet_period(Duration::ZERO),
+            ConnectionConfig::new().operation_deadline(Duration::ZERO)
+        ];
+        for config in configs {
+            match config.validate() {
+                Err(CrawlerError::InvalidConfig(_)) => {},
+                other => panic!("{:?}", other)
+            }
+        }
+        // 按鍵間隔預設就是 0
+        assert!(ConnectionConfig::new().key_interval(Duration::ZERO).validate().is_ok());
+    }
+}
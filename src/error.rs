@@ -1,22 +1,72 @@
+use std::error::Error;
 use std::fmt;
+use std::io;
 
 #[derive(Debug)]
 pub enum CrawlerError {
     UsernameOrPasswordWrong,
-    SomethingWrong(u32)
+    BoardNotFound(String),
+    Io(io::Error),
+    ConnectFailed(io::Error),
+    WebSocket(Box<tungstenite::Error>),
+    Ssh(Box<russh::Error>),
+    Timeout { waiting_for: String },
+    UnexpectedScreen { expected: String, snapshot: String },
+    ReplayMismatch { expected: Vec<u8>, actual: Vec<u8> },
+    InvalidKey(String),
+    InvalidConfig(String),
+    UnknownHostKey(String),
+    Decode(String)
 }
 
-impl CrawlerError {
-    pub fn to_string(&self) -> String {
-        match self {
-            &CrawlerError::UsernameOrPasswordWrong => format!("帳號或密碼錯誤"),
-            &CrawlerError::SomethingWrong(code) => format!("好像哪裡怪怪的？ Code: {}", code)
+impl fmt::Display for CrawlerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CrawlerError::UsernameOrPasswordWrong => write!(f, "帳號或密碼錯誤"),
+            CrawlerError::BoardNotFound(ref name) => write!(f, "找不到看板：{}", name),
+            CrawlerError::Io(ref e) => write!(f, "IO 錯誤：{}", e),
+            CrawlerError::ConnectFailed(ref e) => write!(f, "無法連到 PTT：{}", e),
+            CrawlerError::WebSocket(ref e) => write!(f, "WebSocket 錯誤：{}", e),
+            CrawlerError::Ssh(ref e) => write!(f, "SSH 錯誤：{}", e),
+            CrawlerError::Timeout { ref waiting_for } => write!(f, "等待「{}」逾時", waiting_for),
+            CrawlerError::UnexpectedScreen { ref expected, .. } =>
+                write!(f, "畫面不如預期，應該要出現「{}」", expected),
+            CrawlerError::ReplayMismatch { ref expected, ref actual } =>
+                write!(f, "送出的資料與紀錄不符：應該是「{}」，實際是「{}」", expected.escape_ascii(), actual.escape_ascii()),
+            CrawlerError::InvalidKey(ref key) => write!(f, "沒有這個按鍵：{}", key),
+            CrawlerError::InvalidConfig(ref msg) => write!(f, "設定錯誤：{}", msg),
+            CrawlerError::UnknownHostKey(ref fingerprint) =>
+                write!(f, "SSH 主機金鑰 {} 不在信任清單中，確認無誤後可以用 ssh_host_key() 加入", fingerprint),
+            CrawlerError::Decode(ref msg) => write!(f, "Big5 編解碼錯誤：{}", msg)
         }
     }
 }
 
-impl fmt::Display for CrawlerError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+impl Error for CrawlerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CrawlerError::Io(ref e) | CrawlerError::ConnectFailed(ref e) => Some(e),
+            CrawlerError::WebSocket(ref e) => Some(&**e),
+            CrawlerError::Ssh(ref e) => Some(&**e),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for CrawlerError {
+    fn from(error: io::Error) -> CrawlerError {
+        CrawlerError::Io(error)
+    }
+}
+
+impl From<tungstenite::Error> for CrawlerError {
+    fn from(error: tungstenite::Error) -> CrawlerError {
+        CrawlerError::WebSocket(Box::new(error))
+    }
+}
+
+impl From<russh::Error> for CrawlerError {
+    fn from(error: russh::Error) -> CrawlerError {
+        CrawlerError::Ssh(Box::new(error))
     }
 }
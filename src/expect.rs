@@ -0,0 +1,197 @@
+use std::fmt;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::screen::{Attributes, Screen};
+
+// 等待畫面出現某種狀態的條件，例如：
+// expect(any![text("歡迎您再度拜訪"), text("請重新輸入")])
+#[derive(Debug, Clone)]
+pub enum Expect {
+    Text(String),                                       // 畫面上任何地方
+    RowText(usize, String),                             // 某一行中
+    Region { rows: (usize, usize), columns: (usize, usize), text: String }, // 第 rows.0 ~ rows.1 行、第 columns.0 ~ columns.1 欄之間 (不含結尾)
+    Regex(Regex),                                       // 任何一行符合
+    Cursor(usize, usize),
+    Attribute { row: usize, columns: (usize, usize), predicate: fn(&Attributes) -> bool }, // 範圍內的每一格都要符合
+    Stable(Duration),                                   // 畫面已經這麼久沒有變化
+    Any(Vec<Expect>),
+    All(Vec<Expect>),
+    Not(Box<Expect>)
+}
+
+pub fn text(pattern: &str) -> Expect {
+    Expect::Text(pattern.to_string())
+}
+
+pub fn row_text(row: usize, pattern: &str) -> Expect {
+    Expect::RowText(row, pattern.to_string())
+}
+
+pub fn region(rows: (usize, usize), columns: (usize, usize), pattern: &str) -> Expect {
+    Expect::Region { rows, columns, text: pattern.to_string() }
+}
+
+// pattern 寫錯時會 panic，就跟 Regex::new(...).unwrap() 一樣
+pub fn regex(pattern: &str) -> Expect {
+    Expect::Regex(Regex::new(pattern).expect("invalid regex"))
+}
+
+pub fn cursor_at(row: usize, column: usize) -> Expect {
+    Expect::Cursor(row, column)
+}
+
+pub fn attribute(row: usize, columns: (usize, usize), predicate: fn(&Attributes) -> bool) -> Expect {
+    Expect::Attribute { row, columns, predicate }
+}
+
+// 選單上被選到的那一行會整行反白
+pub fn reverse_row(row: usize) -> Expect {
+    Expect::Attribute { row, columns: (0, usize::MAX), predicate: |a| a.reverse }
+}
+
+pub fn stable(duration: Duration) -> Expect {
+    Expect::Stable(duration)
+}
+
+pub fn not(expect: Expect) -> Expect {
+    Expect::Not(Box::new(expect))
+}
+
+#[macro_export]
+macro_rules! any {
+    ($($expect:expr),+ $(,)?) => {
+        $crate::expect::Expect::Any(vec![$($expect),+])
+    };
+}
+
+#[macro_export]
+macro_rules! all {
+    ($($expect:expr),+ $(,)?) => {
+        $crate::expect::Expect::All(vec![$($expect),+])
+    };
+}
+
+impl Expect {
+    // 符合時回傳是第幾個選項符合 (只有 Any 會不是 0)，idle 是畫面多久沒有變化了
+    pub fn check(&self, screen: &Screen, idle: Duration) -> Option<usize> {
+        let matched = match *self {
+            Expect::Text(ref pattern) => screen.check_string(pattern),
+            Expect::RowText(row, ref pattern) => row < screen.height() && screen.row_text(row).contains(pattern.as_str()),
+            Expect::Region { rows, columns, ref text } => {
+                (rows.0 .. rows.1.min(screen.height())).any(|row| region_text(screen, row, columns).contains(text.as_str()))
+            },
+            Expect::Regex(ref regex) => (0 .. screen.height()).any(|row| regex.is_match(&screen.row_text(row))),
+            Expect::Cursor(row, column) => screen.get_cursor_position() == (row, column),
+            Expect::Attribute { row, columns, predicate } => {
+                row < screen.height() && columns.0 < screen.width()
+                    && (columns.0 .. columns.1.min(screen.width())).all(|c| predicate(&screen.attributes_at(row, c)))
+            },
+            Expect::Stable(duration) => idle >= duration,
+            Expect::Any(ref expects) => return expects.iter().position(|e| e.check(screen, idle).is_some()),
+            Expect::All(ref expects) => expects.iter().all(|e| e.check(screen, idle).is_some()),
+            Expect::Not(ref expect) => expect.check(screen, idle).is_none()
+        };
+        if matched { Some(0) } else { None }
+    }
+
+    // 等待時至少要容忍多久沒有新資料
+    pub fn get_stable_duration(&self) -> Duration {
+        match *self {
+            Expect::Stable(duration) => duration,
+            Expect::Any(ref expects) | Expect::All(ref expects) => {
+                expects.iter().map(Expect::get_stable_duration).max().unwrap_or_default()
+            },
+            Expect::Not(ref expect) => expect.get_stable_duration(),
+            _ => Duration::ZERO
+        }
+    }
+}
+
+// 某一行在 columns 範圍內的文字，跨過邊界的雙位元組字不算
+fn region_text(screen: &Screen, row: usize, columns: (usize, usize)) -> String {
+    let row_text = screen.row_text_columns(row);
+    row_text.text.chars().zip(row_text.columns.iter())
+        .filter(|&(ch, &c)| c >= columns.0 && c + if ch.is_ascii() { 1 } else { 2 } <= columns.1)
+        .map(|(ch, _)| ch)
+        .collect()
+}
+
+impl fmt::Display for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expect::Text(ref pattern) => write!(f, "{}", pattern),
+            Expect::RowText(row, ref pattern) => write!(f, "第 {} 行的 {}", row, pattern),
+            Expect::Region { rows, columns, ref text } =>
+                write!(f, "第 {}~{} 行、第 {}~{} 欄的 {}", rows.0, rows.1, columns.0, columns.1, text),
+            Expect::Regex(ref regex) => write!(f, "/{}/", regex),
+            Expect::Cursor(row, column) => write!(f, "游標在 ({}, {})", row, column),
+            Expect::Attribute { row, .. } => write!(f, "第 {} 行的屬性", row),
+            Expect::Stable(duration) => write!(f, "畫面 {} ms 沒有變化", duration.as_millis()),
+            Expect::Any(ref expects) => write_joined(f, expects, " / "),
+            Expect::All(ref expects) => write_joined(f, expects, " + "),
+            Expect::Not(ref expect) => write!(f, "不是 ({})", expect)
+        }
+    }
+}
+
+fn write_joined(f: &mut fmt::Formatter, expects: &[Expect], separator: &str) -> fmt::Result {
+    for (i, expect) in expects.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", separator)?;
+        }
+        write!(f, "{}", expect)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{attribute, cursor_at, not, region, regex, reverse_row, row_text, stable, text};
+    use crate::screen::Screen;
+
+    fn screen() -> Screen {
+        let mut screen = Screen::new();
+        screen.process(b"\x1b[1;1H\xa4\xa4\xa4\xe5 title");
+        screen.process(b"\x1b[3;1H\x1b[7m> 1234 reversed        \x1b[m");
+        screen.process(b"\x1b[24;1H 1/3 page");
+        screen
+    }
+
+    #[test]
+    fn any_reports_the_matching_alternative() {
+        let screen = screen();
+        assert_eq!(any![text("nothing"), text("title")].check(&screen, Duration::ZERO), Some(1));
+        assert_eq!(any![text("nothing"), text("none")].check(&screen, Duration::ZERO), None);
+        assert_eq!(all![text("中文"), not(text("none"))].check(&screen, Duration::ZERO), Some(0));
+        assert_eq!(all![text("中文"), text("none")].check(&screen, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn rows_and_regions() {
+        let screen = screen();
+        assert!(row_text(0, "title").check(&screen, Duration::ZERO).is_some());
+        assert!(row_text(1, "title").check(&screen, Duration::ZERO).is_none());
+        assert!(region((0, 1), (0, 4), "中文").check(&screen, Duration::ZERO).is_some());
+        assert!(region((0, 1), (1, 4), "中文").check(&screen, Duration::ZERO).is_none());
+        assert!(region((0, 1), (5, 10), "title").check(&screen, Duration::ZERO).is_some());
+        assert!(regex(r"\d+/\d+ page").check(&screen, Duration::ZERO).is_some());
+    }
+
+    #[test]
+    fn cursor_attributes_and_stability() {
+        let screen = screen();
+        assert!(cursor_at(23, 9).check(&screen, Duration::ZERO).is_some());
+        assert!(attribute(2, (0, 20), |a| a.reverse).check(&screen, Duration::ZERO).is_some());
+        assert!(reverse_row(2).check(&screen, Duration::ZERO).is_none());
+        assert!(reverse_row(0).check(&screen, Duration::ZERO).is_none());
+
+        let stable = stable(Duration::from_millis(500));
+        assert!(stable.check(&screen, Duration::from_millis(100)).is_none());
+        assert!(stable.check(&screen, Duration::from_millis(600)).is_some());
+        assert_eq!(any![text("none"), stable].get_stable_duration(), Duration::from_millis(500));
+    }
+}
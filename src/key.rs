@@ -0,0 +1,92 @@
+use crate::error::CrawlerError;
+use crate::screen::ScreenEncoding;
+
+// 送給 PTT 的按鍵，文字會用畫面的編碼 (Big5-UAO) 轉換
+#[derive(Debug, Clone, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Enter,
+    Escape,
+    Space,
+    Tab,
+    Backspace,
+    Ctrl(char),    // Ctrl-A ~ Ctrl-Z，例如 Ctrl('p') 發表文章
+    Function(u8),  // F1 ~ F12
+    Char(char),
+    Text(String)
+}
+
+impl Key {
+    pub fn text(text: &str) -> Key {
+        Key::Text(text.to_string())
+    }
+
+    pub fn to_bytes(&self, encoding: &dyn ScreenEncoding) -> Result<Vec<u8>, CrawlerError> {
+        let bytes: &[u8] = match *self {
+            Key::Up => b"\x1b[A",
+            Key::Down => b"\x1b[B",
+            Key::Right => b"\x1b[C",
+            Key::Left => b"\x1b[D",
+            Key::Home => b"\x1b[1~",
+            Key::End => b"\x1b[4~",
+            Key::PageUp => b"\x1b[5~",
+            Key::PageDown => b"\x1b[6~",
+            Key::Enter => b"\r\n",
+            Key::Escape => b"\x1b",
+            Key::Space => b" ",
+            Key::Tab => b"\t",
+            Key::Backspace => b"\x08",
+            Key::Ctrl(ch) if ch.is_ascii_alphabetic() => return Ok(vec![ch.to_ascii_uppercase() as u8 - b'@']),
+            Key::Function(n) => match n {
+                1 => b"\x1bOP",
+                2 => b"\x1bOQ",
+                3 => b"\x1bOR",
+                4 => b"\x1bOS",
+                5 => b"\x1b[15~",
+                6 => b"\x1b[17~",
+                7 => b"\x1b[18~",
+                8 => b"\x1b[19~",
+                9 => b"\x1b[20~",
+                10 => b"\x1b[21~",
+                11 => b"\x1b[23~",
+                12 => b"\x1b[24~",
+                _ => return Err(CrawlerError::InvalidKey(format!("F{}", n)))
+            },
+            Key::Ctrl(ch) => return Err(CrawlerError::InvalidKey(format!("Ctrl-{}", ch))),
+            Key::Char(ch) => return encoding.encode(ch.encode_utf8(&mut [0; 4])),
+            Key::Text(ref text) => return encoding.encode(text)
+        };
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Key;
+    use crate::screen::Big5Uao;
+
+    #[test]
+    fn named_keys() {
+        let encoding = Big5Uao::new();
+        assert_eq!(Key::PageDown.to_bytes(&encoding).unwrap(), b"\x1b[6~");
+        assert_eq!(Key::Ctrl('p').to_bytes(&encoding).unwrap(), b"\x10");
+        assert_eq!(Key::Ctrl('Z').to_bytes(&encoding).unwrap(), b"\x1a");
+        assert_eq!(Key::Function(1).to_bytes(&encoding).unwrap(), b"\x1bOP");
+        assert!(Key::Function(13).to_bytes(&encoding).is_err());
+        assert!(Key::Ctrl('1').to_bytes(&encoding).is_err());
+    }
+
+    #[test]
+    fn text_is_encoded() {
+        let encoding = Big5Uao::new();
+        assert_eq!(Key::text("八卦").to_bytes(&encoding).unwrap(), b"\xa4\x4b\xa8\xf6");
+        assert_eq!(Key::Char('y').to_bytes(&encoding).unwrap(), b"y");
+    }
+}
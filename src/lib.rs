@@ -0,0 +1,25 @@
+pub mod aid;
+mod article;
+mod board;
+mod comment;
+mod config;
+mod error;
+pub mod expect;
+mod key;
+mod login;
+mod ptt;
+pub mod screen;
+pub mod transport;
+
+pub use aid::ArticleId;
+pub use article::{Article, ArticleHeader, ArticleReader, BrowseStatus};
+pub use board::{parse_article_list, ArticleListEntry, PushCount};
+pub use comment::{parse_comment, Comment, CommentKind};
+pub use config::{ConnectionConfig, TransportKind};
+pub use error::CrawlerError;
+pub use expect::Expect;
+pub use key::Key;
+pub use login::LoginEvent;
+pub use ptt::PttConnection;
+pub use screen::Screen;
+pub use transport::Transport;
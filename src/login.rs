@@ -0,0 +1,104 @@
+use std::fmt;
+
+use crate::screen::Screen;
+
+// 輸入密碼之後到主功能表之前可能出現的畫面
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoginEvent {
+    Loading,                  // 登入中，請稍候...
+    DuplicateLogin,           // 您想刪除其他重複登入的連線嗎？
+    FailedAttempts,           // 您要刪除以上錯誤嘗試的記錄嗎?
+    TooFrequent,              // 請勿頻繁登入以免造成系統過度負荷
+    MailboxFull,              // 您保存信件數目 ... 超出上限
+    Welcome,                  // 歡迎您再度拜訪
+    Announcement(Vec<String>) // 其他要按任意鍵繼續的畫面 (週年慶、站方公告等)，內容是畫面上每一行非空白的文字
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoginScreen {
+    MainMenu,
+    WrongPassword,
+    Interstitial(LoginEvent)
+}
+
+impl fmt::Display for LoginEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoginEvent::Loading => write!(f, "登入中"),
+            LoginEvent::DuplicateLogin => write!(f, "重複登入"),
+            LoginEvent::FailedAttempts => write!(f, "有密碼輸入錯誤的記錄"),
+            LoginEvent::TooFrequent => write!(f, "登入太頻繁"),
+            LoginEvent::MailboxFull => write!(f, "信箱已滿"),
+            LoginEvent::Welcome => write!(f, "歡迎畫面"),
+            LoginEvent::Announcement(ref lines) => write!(f, "公告：{}", lines.first().map_or("", |l| l.as_str()))
+        }
+    }
+}
+
+// 依序比對，越前面的越優先 (例如歡迎畫面上也有「請按任意鍵繼續」)
+const INTERSTITIALS: [(&str, LoginEvent); 6] = [
+    ("您想刪除其他重複登入的連線嗎", LoginEvent::DuplicateLogin),
+    ("您要刪除以上錯誤嘗試的記錄嗎", LoginEvent::FailedAttempts),
+    ("請勿頻繁登入", LoginEvent::TooFrequent),
+    ("您保存信件數目", LoginEvent::MailboxFull),
+    ("歡迎您再度拜訪", LoginEvent::Welcome),
+    ("登入中", LoginEvent::Loading)
+];
+
+// 判斷登入過程中目前停在哪一個畫面，還認不出來 (例如畫面還沒畫完) 時回傳 None
+pub fn parse_login_screen(screen: &Screen) -> Option<LoginScreen> {
+    if screen.check_string("主功能表") {
+        return Some(LoginScreen::MainMenu);
+    }
+    if screen.check_string("請重新輸入") || screen.check_string("密碼不對") {
+        return Some(LoginScreen::WrongPassword);
+    }
+    for (pattern, event) in INTERSTITIALS.iter() {
+        if screen.check_string(pattern) {
+            return Some(LoginScreen::Interstitial(event.clone()));
+        }
+    }
+    if screen.check_string("任意鍵") {
+        let lines = (0 .. screen.height())
+            .map(|row| screen.row_text(row).trim().to_string())
+            .filter(|line| !line.is_empty() && !line.contains("任意鍵"))
+            .collect();
+        return Some(LoginScreen::Interstitial(LoginEvent::Announcement(lines)));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_login_screen, LoginEvent, LoginScreen};
+    use crate::screen::{Big5, Screen, ScreenEncoding};
+
+    fn screen(rows: &[(usize, &str)]) -> Screen {
+        let mut screen = Screen::new();
+        for &(row, text) in rows {
+            screen.process(format!("\x1b[{};1H", row + 1).as_bytes());
+            screen.process(&Big5.encode(text).unwrap());
+        }
+        screen
+    }
+
+    #[test]
+    fn welcome_wins_over_press_any_key() {
+        let welcome = screen(&[(8, "歡迎您再度拜訪，上次您是從 1.2.3.4 連往本站。"), (23, "請按任意鍵繼續")]);
+        assert_eq!(parse_login_screen(&welcome), Some(LoginScreen::Interstitial(LoginEvent::Welcome)));
+    }
+
+    #[test]
+    fn unknown_pages_are_announcements() {
+        let page = screen(&[(3, "  批踢踢 26 週年"), (5, "感謝大家"), (23, "  ● 請按任意鍵繼續 ●")]);
+        let expected = LoginEvent::Announcement(vec!["批踢踢 26 週年".to_string(), "感謝大家".to_string()]);
+        assert_eq!(parse_login_screen(&page), Some(LoginScreen::Interstitial(expected)));
+    }
+
+    #[test]
+    fn main_menu_and_wrong_password() {
+        assert_eq!(parse_login_screen(&screen(&[(0, "【主功能表】")])), Some(LoginScreen::MainMenu));
+        assert_eq!(parse_login_screen(&screen(&[(21, "密碼不對或無此帳號。")])), Some(LoginScreen::WrongPassword));
+        assert_eq!(parse_login_screen(&screen(&[(10, "系統負荷")])), None);
+    }
+}
@@ -1,38 +1,55 @@
-extern crate encoding;
-extern crate telnet;
-extern crate ansi_escapes;
-
-mod error;
-mod screen;
-mod ptt;
+extern crate ptt_crawler;
 
 use std::env;
+use std::fs;
+
+use ptt_crawler::{CrawlerError, PttConnection};
 
-use error::CrawlerError;
-use ptt::PttConnection;
+// 發生錯誤時把畫面存成 HTML，方便附在問題回報中
+const SCREEN_DUMP_PATH: &str = "ptt-screen.html";
 
 fn main() {
     if env::args().count() < 3 {
-        println!("Usage: {} [account] [password]", env::args().nth(0).unwrap());
+        println!("Usage: {} [account] [password] ([board])", env::args().next().unwrap());
         return;
     }
     let account = env::args().nth(1).unwrap();
     let password = env::args().nth(2).unwrap();
-
-    let mut connection = PttConnection::new();
-    if let Err(error_type) = execute(&mut connection, &account, &password) {
+    let board = env::args().nth(3);
+
+    let mut connection = match PttConnection::new() {
+        Ok(connection) => connection,
+        Err(error_type) => {
+            println!("Error: {}", error_type);
+            return;
+        }
+    };
+    if let Err(error_type) = execute(&mut connection, &account, &password, board) {
         println!("Error: {}", error_type);
         println!("Print the current screen:");
-        connection.print_screen();
+        for (i, line) in connection.get_screen().snapshot().lines().enumerate() {
+            println!("{:02}| {}", i + 1, line);
+        }
+        match fs::write(SCREEN_DUMP_PATH, connection.get_screen().to_html()) {
+            Ok(_) => println!("The screen is also saved to {}", SCREEN_DUMP_PATH),
+            Err(error) => println!("Failed to save the screen: {}", error)
+        }
     }
 }
 
-fn execute(connection: &mut PttConnection, account: &str, password: &str) -> Result<(), CrawlerError> {
-    connection.login(account, password)?;
-    connection.go_to_first_board()?;
+fn execute(connection: &mut PttConnection, account: &str, password: &str, board: Option<String>) -> Result<(), CrawlerError> {
+    connection.login_with(account, password, |event| println!("登入時遇到：{}", event))?;
+    println!("登入成功！");
 
-    // Debug
-    connection.print_screen();
+    match board {
+        Some(name) => connection.enter_board(&name)?,
+        None => connection.go_to_first_board()?
+    }
+    println!("進入看板！");
+
+    for entry in connection.article_list() {
+        println!("{}", entry);
+    }
 
     Ok(())
 }
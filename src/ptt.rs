@@ -1,137 +1,506 @@
 
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use telnet::{Telnet, TelnetEvent};
+use crate::aid::{self, ArticleId};
+use crate::article::{Article, ArticleReader};
+use crate::board::{self, ArticleListEntry, PushCount};
+use crate::config::{ConnectionConfig, TransportKind};
+use crate::error::CrawlerError;
+use crate::{all, any};
+use crate::expect::{self, Expect};
+use crate::key::Key;
+use crate::login::{self, LoginEvent, LoginScreen};
+use crate::screen::{Screen, ScreenEncoding};
+use crate::transport::{Transport, RecordingTransport, SshTransport, TelnetTransport, WebSocketTransport};
 
-use error::CrawlerError;
-use screen::Screen;
+// 登入時最多處理幾個要按鍵的畫面，超過就當作卡住了
+const MAX_LOGIN_SCREENS: usize = 10;
 
-const BYTE_CR: u8 = '\r' as u8;
-const BYTE_LF: u8 = '\n' as u8;
-const BYTE_SPACE: u8 = ' ' as u8;
-
-const ARROW_UP: [u8; 3] = [27, '[' as u8, 'A' as u8];
-const ARROW_DOWN: [u8; 3] = [27, '[' as u8, 'B' as u8];
-const ARROW_RIGHT: [u8; 3] = [27, '[' as u8, 'C' as u8];
-const ARROW_LEFT: [u8; 3] = [27, '[' as u8, 'D' as u8];
+// 傳給 transport 的最短讀取逾時
+const MIN_READ_TIMEOUT: Duration = Duration::from_millis(1);
 
 pub struct PttConnection {
-    tel_conn: Telnet,
-    screen: Screen
+    config: ConnectionConfig,
+    transport: Box<dyn Transport>,
+    screen: Screen,
+    last_update: Instant,  // 最後一次收到資料的時間
+    last_write: Instant,   // 最後一次送出按鍵的時間
+    quiet_period: Duration // 多久沒有資料就當作畫面畫完了，見 observe_gap
 }
 
 impl PttConnection {
-    pub fn new() -> PttConnection {
-        PttConnection {
-            tel_conn: Telnet::connect(("ptt.cc", 23), 256).expect("無法連到 PTT"),
-            screen: Screen::new()
+    pub fn new() -> Result<PttConnection, CrawlerError> {
+        PttConnection::connect(ConnectionConfig::new())
+    }
+
+    pub fn connect(config: ConnectionConfig) -> Result<PttConnection, CrawlerError> {
+        config.validate()?;
+        let mut transport: Box<dyn Transport> = match *config.get_transport() {
+            TransportKind::Telnet => Box::new(TelnetTransport::connect(&config)?),
+            TransportKind::WebSocket(ref url) => Box::new(WebSocketTransport::connect(url)?),
+            TransportKind::Ssh(ref user) => Box::new(SshTransport::connect(&config, user)?)
+        };
+        if let Some(path) = config.get_record_path() {
+            transport = Box::new(RecordingTransport::create(transport, path)?);
         }
+        PttConnection::with_transport(transport, config)
+    }
+
+    pub fn with_transport(transport: Box<dyn Transport>, config: ConnectionConfig) -> Result<PttConnection, CrawlerError> {
+        config.validate()?;
+        let (width, height) = config.get_terminal_size();
+        let quiet_period = config.get_quiet_period();
+        Ok(PttConnection {
+            config,
+            transport,
+            screen: Screen::with_size(width, height),
+            last_update: Instant::now(),
+            last_write: Instant::now(),
+            quiet_period
+        })
+    }
+
+    pub fn get_screen(&self) -> &Screen {
+        &self.screen
     }
 
-    pub fn print_screen(&self) {
-        self.screen.print_screen();
+    pub fn set_encoding(&mut self, encoding: Box<dyn ScreenEncoding>) {
+        self.screen.set_encoding(encoding);
     }
 
-    pub fn login(&mut self, account: &str, password: &str) -> Result<(), CrawlerError> {
-        self.skip_until("請輸入代號");
-        self.tel_conn.write(account.as_bytes()).expect("寫入錯誤");
-        self.write_enter();
-        self.tel_conn.write(password.as_bytes()).expect("寫入錯誤");
-        self.write_enter();
+    // 登入並處理到主功能表之前的各種畫面，回傳途中遇到了哪些畫面
+    pub fn login(&mut self, account: &str, password: &str) -> Result<Vec<LoginEvent>, CrawlerError> {
+        let mut events = vec![];
+        self.login_with(account, password, |event| events.push(event.clone()))?;
+        Ok(events)
+    }
+
+    // 跟 login 一樣，但每處理一個畫面就呼叫 on_event，登入失敗時也知道已經遇到了哪些畫面
+    pub fn login_with<F>(&mut self, account: &str, password: &str, mut on_event: F) -> Result<(), CrawlerError>
+        where F: FnMut(&LoginEvent) {
+        self.expect(&expect::text("請輸入代號"))?;
+        self.send_keys(&[Key::text(account), Key::Enter, Key::text(password), Key::Enter])?;
 
-        // XXX: 因為它可能出現「載入中」的字樣，也許是要改成判斷「請重新輸入」或「歡迎您再度拜訪」
-        // 其中之一是否有出現
+        let mut handled = 0;
+        let mut loading = false;
+        let mut current = self.wait_for_login_screen()?;
+        loop {
+            let event = match current {
+                LoginScreen::MainMenu => return Ok(()),
+                LoginScreen::WrongPassword => return Err(CrawlerError::UsernameOrPasswordWrong),
+                LoginScreen::Interstitial(event) => event
+            };
 
-        if !self.skip_until("歡迎您再度拜訪") {
-            if self.screen.check_string("請重新輸入") {
-                return Err(CrawlerError::UsernameOrPasswordWrong);
+            // 登入中的畫面不用按鍵，等它自己換掉
+            if event == LoginEvent::Loading {
+                if !loading {
+                    on_event(&event);
+                }
+                loading = true;
+                current = self.wait_for_login_screen()?;
+                continue;
+            }
+            loading = false;
+
+            on_event(&event);
+            match event {
+                LoginEvent::DuplicateLogin => {
+                    let answer = if self.config.get_kick_duplicate_login() { 'y' } else { 'n' };
+                    self.send_keys(&[Key::Char(answer), Key::Enter])?;
+                },
+                LoginEvent::FailedAttempts => self.send_keys(&[Key::Char('y'), Key::Enter])?,
+                _ => self.send_keys(&[Key::Space])?
+            }
+            handled += 1;
+            if handled > MAX_LOGIN_SCREENS {
+                return Err(self.unexpected_screen("主功能表"));
             }
-            return Err(CrawlerError::SomethingWrong(1));
+
+            // 回答之後等畫面換掉，如果還認不出來就繼續等
+            self.settle()?;
+            current = match login::parse_login_screen(&self.screen) {
+                Some(screen) => screen,
+                None => self.wait_for_login_screen()?
+            };
         }
+    }
 
-        println!("登入成功！");
+    pub fn go_to_first_board(&mut self) -> Result<(), CrawlerError> {
+        self.enter_board("Gossiping")
+    }
 
-        let mut try_count = 0;
-        while !self.screen.check_string("主功能表") {
-            self.tel_conn.write(&[BYTE_SPACE]).expect("寫入錯誤");
-            self.read_to_timeout();
+    pub fn enter_board(&mut self, name: &str) -> Result<(), CrawlerError> {
+        // 用主功能表的 (s) 搜尋看板
+        self.send_keys(&[Key::Char('s')])?;
+        self.expect(&expect::text("請輸入看板名稱"))?;
+        self.send_keys(&[Key::text(name), Key::Enter])?;
 
-            try_count += 1;
-            if try_count > 5 {
-                return Err(CrawlerError::SomethingWrong(2));
+        // 找不到看板時 PTT 會直接回到主功能表，搜尋的提示也會消失
+        let board_screens = any![
+            expect::text("看板《"),
+            expect::text("請按任意鍵繼續"),
+            expect::text("滿十八歲"),
+            all![expect::text("【主功能表】"), expect::not(expect::text("請輸入看板名稱"))]
+        ];
+        loop {
+            match self.expect(&board_screens)? {
+                0 => break,
+                1 => self.send_keys(&[Key::Space])?,
+                2 => self.send_keys(&[Key::Char('y'), Key::Enter])?,
+                _ => return Err(CrawlerError::BoardNotFound(name.to_string()))
             }
+
+            // 等畫面換掉，否則同一個提示會被回答兩次
+            self.settle()?;
+        }
+
+        // 看到標題時文章列表可能還沒畫完
+        self.settle_until(&self.list_ready())?;
+
+        // 看板名稱的大小寫可能跟輸入的不同
+        let header = self.screen.row_text(0).to_lowercase();
+        if !header.contains(&format!("看板《{}》", name.to_lowercase())) {
+            return Err(CrawlerError::BoardNotFound(name.to_string()));
         }
 
         Ok(())
     }
 
-    pub fn go_to_first_board(&mut self) -> Result<(), CrawlerError> {
-        self.tel_conn.write(&ARROW_RIGHT).expect("寫入錯誤");
-        self.skip_until("即時熱門看板");
-        self.tel_conn.write(&ARROW_UP).expect("寫入錯誤");
-        self.skip_until_cursor_at(19, 10);
-        self.tel_conn.write(&ARROW_RIGHT).expect("寫入錯誤");
-        self.skip_until("Gossiping");
-        self.tel_conn.write(&ARROW_RIGHT).expect("寫入錯誤");
-        self.skip_until("請按任意鍵繼續");
-        self.tel_conn.write(&ARROW_RIGHT).expect("寫入錯誤");
+    // 目前畫面上的文章列表
+    // 列表上沒有文章代碼，id 都是 None，需要的話再用 fill_article_ids 查
+    pub fn article_list(&self) -> Vec<ArticleListEntry> {
+        board::parse_article_list(&self.screen)
+    }
+
+    pub fn next_article_page(&mut self) -> Result<(), CrawlerError> {
+        self.send_keys(&[Key::PageDown])?;
+        self.settle_until(&self.list_ready())
+    }
 
-        if !self.skip_until("看板《Gossiping》") {
-            self.screen.print_screen();
-            return Err(CrawlerError::SomethingWrong(3));
+    pub fn previous_article_page(&mut self) -> Result<(), CrawlerError> {
+        self.send_keys(&[Key::PageUp])?;
+        self.settle_until(&self.list_ready())
+    }
+
+    // 從第一篇開始往下翻，直到翻不出新的文章為止
+    // id 都是 None，需要的話再用 fill_article_ids 查，或改用 all_article_list_with_ids
+    pub fn all_article_list(&mut self) -> Result<Vec<ArticleListEntry>, CrawlerError> {
+        self.send_keys(&[Key::Home])?;
+        self.settle_until(&self.list_ready())?;
+        self.collect_article_list()
+    }
+
+    // 跟 all_article_list 一樣，但會一篇一篇查出文章代碼 (見 fill_article_ids)
+    pub fn all_article_list_with_ids(&mut self) -> Result<Vec<ArticleListEntry>, CrawlerError> {
+        let mut entries = self.all_article_list()?;
+        self.fill_article_ids(&mut entries)?;
+        Ok(entries)
+    }
+
+    // 用 / 搜尋標題含有 keyword 的文章
+    // 搜尋結果跟 all_article_list 一樣沒有文章代碼，需要的話再用 fill_article_ids 查
+    pub fn search_title(&mut self, keyword: &str) -> Result<Vec<ArticleListEntry>, CrawlerError> {
+        let lowercase = keyword.to_lowercase();
+        self.search('/', keyword, |entry| entry.title.to_lowercase().contains(&lowercase))
+    }
+
+    // 用 a 搜尋某位作者的文章
+    // id 都是 None，需要的話再用 fill_article_ids 查
+    pub fn search_author(&mut self, author: &str) -> Result<Vec<ArticleListEntry>, CrawlerError> {
+        self.search('a', author, |entry| entry.author.eq_ignore_ascii_case(author))
+    }
+
+    // 用 Z 搜尋推文數至少 min 的文章，min 是負數時改找噓文數至少 -min 的文章
+    // id 都是 None，需要的話再用 fill_article_ids 查
+    pub fn search_push_count(&mut self, min: i32) -> Result<Vec<ArticleListEntry>, CrawlerError> {
+        self.search('Z', &min.to_string(), |entry| {
+            let score = entry.push_count.as_ref().map_or(0, PushCount::get_score);
+            if min >= 0 { score >= min } else { score <= min }
+        })
+    }
+
+    // 用 # 跳到文章代碼 (AID) 對應的文章，找不到時回傳 None
+    pub fn find_article_by_aid(&mut self, aid: &str) -> Result<Option<ArticleListEntry>, CrawlerError> {
+        self.send_keys(&[Key::Char('#')])?;
+        self.expect(&self.prompt_ready())?;
+        self.send_keys(&[Key::text(aid.trim_start_matches('#')), Key::Enter])?;
+        self.settle_until(&any![self.list_ready(), expect::text("請按任意鍵繼續")])?;
+
+        if self.screen.check_string("找不到這個文章代碼") {
+            self.send_keys(&[Key::Space])?;
+            self.settle_until(&self.list_ready())?;
+            return Ok(None);
         }
+        Ok(board::parse_selected_article(&self.screen).map(|mut entry| {
+            entry.id = ArticleId::from_aid(aid);
+            entry
+        }))
+    }
 
-        println!("進入八卦版！");
+    // 按 Q 查游標所在文章的文章代碼，已被刪除的文章查不到
+    pub fn selected_article_id(&mut self) -> Result<Option<ArticleId>, CrawlerError> {
+        self.send_keys(&[Key::Char('Q')])?;
+        self.settle_until(&any![self.list_ready(), expect::text("請按任意鍵繼續")])?;
+        if !self.screen.check_string("請按任意鍵繼續") {
+            return Ok(None);
+        }
+
+        let id = aid::parse_article_info(&self.screen);
+        self.send_keys(&[Key::Space])?;
+        self.settle_until(&self.list_ready())?;
+        Ok(id)
+    }
 
+    // 一篇一篇跳過去查文章代碼，每篇要多來回兩次
+    // 置底文章沒有編號可以跳過去，維持 None
+    pub fn fill_article_ids(&mut self, entries: &mut [ArticleListEntry]) -> Result<(), CrawlerError> {
+        for entry in entries.iter_mut() {
+            match entry.index {
+                Some(index) if !entry.deleted && entry.id.is_none() => {
+                    self.select_article(index)?;
+                    entry.id = self.selected_article_id()?;
+                },
+                _ => {}
+            }
+        }
         Ok(())
     }
 
-    fn skip_until(&mut self, pattern: &str) -> bool {
+    // 搜尋結果中的文章保留原本的編號，看完後按 ← 回到原本的列表
+    // 沒有符合的文章時 PTT 不會進入搜尋結果，畫面會停在原本的列表
+    fn search<F>(&mut self, key: char, query: &str, matches: F) -> Result<Vec<ArticleListEntry>, CrawlerError>
+        where F: Fn(&ArticleListEntry) -> bool {
+        let before = self.article_list();
+        self.send_keys(&[Key::Char(key)])?;
+        self.expect(&self.prompt_ready())?;
+        self.send_keys(&[Key::text(query), Key::Enter])?;
+        self.settle_until(&self.list_ready())?;
+
+        // 畫面沒變，而且不是每一篇都符合 (標題太長會被截掉，所以不能只看這個)，代表沒有搜尋結果
+        let shown = self.article_list();
+        let mut articles = shown.iter().filter(|entry| entry.index.is_some()).peekable();
+        let all_match = articles.peek().is_some() && articles.all(&matches);
+        if shown == before && !all_match {
+            return Ok(vec![]);
+        }
+
+        self.send_keys(&[Key::Home])?;
+        self.settle_until(&self.list_ready())?;
+        let entries = self.collect_article_list()?.into_iter().filter(|entry| entry.index.is_some()).collect();
+
+        self.send_keys(&[Key::Left])?;
+        self.settle_until(&self.list_ready())?;
+        Ok(entries)
+    }
+
+    // 從目前這一頁往下翻，直到翻不出新的文章為止，置底文章放在最後
+    fn collect_article_list(&mut self) -> Result<Vec<ArticleListEntry>, CrawlerError> {
+        let mut entries: Vec<ArticleListEntry> = vec![];
+        let mut pinned: Vec<ArticleListEntry> = vec![];
         loop {
-            let event = self.tel_conn.read_timeout(Duration::new(3, 0)).expect("IO 錯誤");
-            if let TelnetEvent::Data(data) = event {
-                self.screen.process(&data);
-                if self.screen.check_string(pattern) {
-                    return true;
+            let last_index = entries.last().and_then(|e| e.index).unwrap_or(0);
+            let mut has_new = false;
+            for entry in self.article_list() {
+                match entry.index {
+                    Some(index) if index > last_index => {
+                        entries.push(entry);
+                        has_new = true;
+                    },
+                    None if !pinned.contains(&entry) => pinned.push(entry),
+                    _ => {}
                 }
-            } else if let TelnetEvent::TimedOut = event {
-                self.screen.print_screen();
-                return false;
-            } else {
-                // print!("{:?}", event);
             }
+
+            if !has_new {
+                break;
+            }
+            self.next_article_page()?;
         }
+
+        entries.append(&mut pinned);
+        Ok(entries)
     }
 
-    fn skip_until_cursor_at(&mut self, rid: usize, cid: usize) -> bool {
+    // 在文章列表中打開指定編號的文章，一頁一頁往下讀完後回到列表
+    // 整篇文章要在 operation_deadline 內讀完
+    pub fn read_article(&mut self, index: u32) -> Result<Article, CrawlerError> {
+        let start = Instant::now();
+        self.select_article(index)?;
+        self.send_keys(&[Key::Right])?;
+        self.expect(&expect::text("瀏覽"))?;
+        self.settle_until(&self.page_ready(1))?;
+
+        let mut reader = ArticleReader::new();
         loop {
-            let event = self.tel_conn.read_timeout(Duration::new(3, 0)).expect("IO 錯誤");
-            if let TelnetEvent::Data(data) = event {
-                self.screen.process(&data);
-                if self.screen.get_cursor_position() == (rid, cid) {
-                    return true;
-                }
-            } else if let TelnetEvent::TimedOut = event {
-                self.screen.print_screen();
-                return false;
-            } else {
-                // print!("{:?}", event);
+            if start.elapsed() > self.config.get_operation_deadline() {
+                return Err(CrawlerError::Timeout { waiting_for: "文章的最後一頁".to_string() });
             }
+            let page = match reader.add_page(&self.screen) {
+                Some(ref status) if status.is_last_page() => break,
+                Some(status) => status.page,
+                None => return Err(self.unexpected_screen("瀏覽 第 x/y 頁"))
+            };
+            self.send_keys(&[Key::PageDown])?;
+            self.settle_until(&self.page_ready(page + 1))?;
         }
+
+        self.send_keys(&[Key::Left])?;
+        self.settle_until(&self.list_ready())?;
+
+        Ok(reader.finish())
     }
 
-    fn read_to_timeout(&mut self) {
+    // 直接輸入編號可以把游標移到該篇文章
+    fn select_article(&mut self, index: u32) -> Result<(), CrawlerError> {
+        self.send_keys(&[Key::Text(index.to_string()), Key::Enter])?;
+        self.settle_until(&self.list_ready())
+    }
+
+    // 等到畫面符合 expect 為止，回傳是哪一個選項符合 (見 Expect::check)
+    // 超過 wait_timeout 都沒有新資料 (或 expect 要求的穩定時間) 就當作逾時
+    pub fn expect(&mut self, expect: &Expect) -> Result<usize, CrawlerError> {
+        let start = Instant::now();
+        let patience = self.config.get_wait_timeout().max(expect.get_stable_duration());
         loop {
-            let event = self.tel_conn.read_timeout(Duration::new(3, 0)).expect("IO 錯誤");
-            if let TelnetEvent::Data(data) = event {
+            let idle = self.last_update.elapsed();
+            if let Some(index) = expect.check(&self.screen, idle) {
+                return Ok(index);
+            }
+            if start.elapsed() > self.config.get_operation_deadline() || idle.min(start.elapsed()) >= patience {
+                return Err(CrawlerError::Timeout { waiting_for: expect.to_string() });
+            }
+
+            // 在等穩定的話，時間到了要醒來再檢查一次
+            let mut timeout = self.config.get_wait_timeout();
+            let stable = expect.get_stable_duration();
+            if stable > idle {
+                timeout = timeout.min(stable - idle);
+            }
+            self.receive_timeout(timeout)?;
+        }
+    }
+
+    // 等到畫面變成登入過程中認得的畫面，登入中的畫面可能會停很久，所以一直等到 deadline
+    fn wait_for_login_screen(&mut self) -> Result<LoginScreen, CrawlerError> {
+        let start = Instant::now();
+        loop {
+            if start.elapsed() > self.config.get_operation_deadline() {
+                return Err(CrawlerError::Timeout { waiting_for: "主功能表".to_string() });
+            }
+            let received = self.receive()?;
+            match login::parse_login_screen(&self.screen) {
+                Some(LoginScreen::Interstitial(LoginEvent::Loading)) if !received => continue,
+                Some(screen) if received => return Ok(screen),
+                _ if !received => {
+                    return Err(CrawlerError::Timeout { waiting_for: "主功能表".to_string() });
+                },
+                _ => {}
+            }
+        }
+    }
+
+    // 文章列表畫完時最後一行是「文章選讀」的說明
+    fn list_ready(&self) -> Expect {
+        expect::row_text(self.screen.height() - 1, "文章選讀")
+    }
+
+    // 搜尋的提示出現在最後一行，例如「[搜尋]關鍵字:」
+    fn prompt_ready(&self) -> Expect {
+        expect::row_text(self.screen.height() - 1, "搜尋")
+    }
+
+    // 文章的第 page 頁畫完時狀態列會顯示頁數
+    fn page_ready(&self, page: usize) -> Expect {
+        expect::row_text(self.screen.height() - 1, &format!("瀏覽 第 {}/", page))
+    }
+
+    fn settle(&mut self) -> Result<(), CrawlerError> {
+        self.settle_until(&Expect::All(vec![]))
+    }
+
+    // 等畫面畫完：還沒收到回應的話先等伺服器回應 (最多 wait_timeout)，之後只要安靜一小段時間而且 ready 符合就算畫完
+    // 安靜的時間會依照觀察到的封包間隔調整，不用每次都等滿 wait_timeout
+    // 伺服器沒有回應或畫面停下來時 ready 仍不符合，就回傳錯誤，不要讓呼叫的人解析舊的畫面
+    fn settle_until(&mut self, ready: &Expect) -> Result<(), CrawlerError> {
+        let start = Instant::now();
+        if self.last_update < self.last_write && !self.receive()? {
+            if ready.check(&self.screen, self.last_update.elapsed()).is_some() {
+                return Ok(());
+            }
+            return Err(CrawlerError::Timeout { waiting_for: ready.to_string() });
+        }
+        loop {
+            if start.elapsed() > self.config.get_operation_deadline() {
+                return Err(CrawlerError::Timeout { waiting_for: format!("畫面停止更新 ({})", ready) });
+            }
+
+            let wait_start = Instant::now();
+            if self.receive_timeout(self.quiet_period)? {
+                self.observe_gap(wait_start.elapsed());
+                continue;
+            }
+            if ready.check(&self.screen, self.last_update.elapsed()).is_some() {
+                return Ok(());
+            }
+
+            // 安靜了一下但畫面還沒畫完 (例如狀態列還沒出現)，代表封包間隔比預估的長
+            let wait_start = Instant::now();
+            if !self.receive()? {
+                return Err(self.unexpected_screen(&ready.to_string()));
+            }
+            self.observe_gap(self.quiet_period + wait_start.elapsed());
+        }
+    }
+
+    // 安靜時間取最近封包間隔的兩倍：變長時馬上跟上，變短時慢慢縮回 quiet_period 的下限
+    fn observe_gap(&mut self, gap: Duration) {
+        let target = (gap * 2).min(self.config.get_wait_timeout()).max(self.config.get_quiet_period());
+        self.quiet_period = if target > self.quiet_period {
+            target
+        } else {
+            (self.quiet_period * 3 + target) / 4
+        };
+    }
+
+    // 讀取下一筆資料並更新畫面，等不到資料時回傳 false
+    fn receive(&mut self) -> Result<bool, CrawlerError> {
+        self.receive_timeout(self.config.get_wait_timeout())
+    }
+
+    // transport 不接受 0 的逾時 (例如 telnet 的 set_read_timeout)，算出來的時間可能剛好是 0
+    fn receive_timeout(&mut self, timeout: Duration) -> Result<bool, CrawlerError> {
+        match self.transport.read_timeout(timeout.max(MIN_READ_TIMEOUT))? {
+            Some(data) => {
                 self.screen.process(&data);
-            } else if let TelnetEvent::TimedOut = event {
-                return;
+                self.last_update = Instant::now();
+                Ok(true)
+            },
+            None => Ok(false)
+        }
+    }
+
+    fn unexpected_screen(&self, expected: &str) -> CrawlerError {
+        CrawlerError::UnexpectedScreen {
+            expected: expected.to_string(),
+            snapshot: self.screen.snapshot()
+        }
+    }
+
+    // 依序送出按鍵，設定了 key_interval 的話每個按鍵之間會等一下
+    pub fn send_keys(&mut self, keys: &[Key]) -> Result<(), CrawlerError> {
+        for (i, key) in keys.iter().enumerate() {
+            if i > 0 && !self.config.get_key_interval().is_zero() {
+                thread::sleep(self.config.get_key_interval());
             }
+            let bytes = key.to_bytes(self.screen.get_encoding())?;
+            self.write(&bytes)?;
         }
+        Ok(())
     }
 
-    fn write_enter(&mut self) {
-        self.tel_conn.write(&[BYTE_CR, BYTE_LF]).expect("寫入錯誤");
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        self.last_write = Instant::now();
+        self.transport.write(bytes)
     }
 }
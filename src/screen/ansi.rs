@@ -1,27 +1,53 @@
-
 const ESC: u8 = 27;
-const ESC_CSI: u8 = '[' as u8;
-const CSI_CUU: u8 = 'A' as u8;
-const CSI_CUD: u8 = 'B' as u8;
-const CSI_CUF: u8 = 'C' as u8;
-const CSI_CUB: u8 = 'D' as u8;
-const CSI_CNL: u8 = 'E' as u8;
-const CSI_CPL: u8 = 'F' as u8;
-const CSI_CHA: u8 = 'G' as u8;
-const CSI_CUP: u8 = 'H' as u8;
-const CSI_ED: u8 = 'J' as u8;
-const CSI_EL: u8 = 'K' as u8;
-const CSI_SU: u8 = 'S' as u8;
-const CSI_SD: u8 = 'T' as u8;
-const CSI_HVP: u8 = 'f' as u8;
-const CSI_SGR: u8 = 'm' as u8;
-const CSI_AUX: u8 = 'i' as u8;
-const CSI_SCP: u8 = 's' as u8;
-const CSI_RCP: u8 = 'u' as u8;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1A;
+const BEL: u8 = 7;
+const DEL: u8 = 0x7F;
+
+const ESC_CSI: u8 = b'[';
+const ESC_OSC: u8 = b']';
+const ESC_DCS: u8 = b'P';
+const ESC_SOS: u8 = b'X';
+const ESC_PM: u8 = b'^';
+const ESC_APC: u8 = b'_';
+const ESC_ST: u8 = b'\\';
+const ESC_DECSC: u8 = b'7';
+const ESC_DECRC: u8 = b'8';
+const ESC_IND: u8 = b'D';
+const ESC_NEL: u8 = b'E';
+const ESC_RI: u8 = b'M';
+const ESC_RIS: u8 = b'c';
+const ESC_DECKPAM: u8 = b'=';
+const ESC_DECKPNM: u8 = b'>';
+
+const CSI_CUU: u8 = b'A';
+const CSI_CUD: u8 = b'B';
+const CSI_CUF: u8 = b'C';
+const CSI_CUB: u8 = b'D';
+const CSI_CNL: u8 = b'E';
+const CSI_CPL: u8 = b'F';
+const CSI_CHA: u8 = b'G';
+const CSI_CUP: u8 = b'H';
+const CSI_ED: u8 = b'J';
+const CSI_EL: u8 = b'K';
+const CSI_SU: u8 = b'S';
+const CSI_SD: u8 = b'T';
+const CSI_HVP: u8 = b'f';
+const CSI_SM: u8 = b'h';
+const CSI_RM: u8 = b'l';
+const CSI_SGR: u8 = b'm';
+const CSI_AUX: u8 = b'i';
+const CSI_DECSTBM: u8 = b'r';
+const CSI_SCP: u8 = b's';
+const CSI_RCP: u8 = b'u';
+
+// 避免沒有結尾的控制序列吃掉太多記憶體
+const MAX_SEQUENCE_LEN: usize = 4096;
 
 pub type ParamList = Vec<Option<i32>>;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum EraseOption {
     EraseToEnd,
     EraseToBeginning,
@@ -29,6 +55,15 @@ pub enum EraseOption {
     EraseEntireAndBuffer
 }
 
+// 無法辨識的 CSI 序列，例如 "ESC [ > 1 ; 2 c" 或 "ESC [ 0 SP q"
+#[derive(Debug, PartialEq)]
+pub struct CsiSequence {
+    pub private: Option<u8>, // 參數前的 '<'、'='、'>' 或 '?'
+    pub params: ParamList,
+    pub intermediates: Vec<u8>,
+    pub final_byte: u8
+}
+
 #[derive(Debug)]
 pub enum AnsiToken<'a> {
     Text(&'a [u8]),
@@ -52,40 +87,79 @@ pub enum AnsiToken<'a> {
     // Scrolling
     ScrollUp(isize),
     ScrollDown(isize),
+    SetScrollRegion(isize, isize), // (top, bottom)，0 表示使用預設值
+    Index,
+    ReverseIndex,
+    NextLine,
 
     // SGR
-    SelectGraphic(Vec<u8>),
+    SelectGraphic(ParamList), // 省略的參數是 None
     ResetGraphic,
 
+    // Modes
+    SetMode(Vec<i32>),
+    ResetMode(Vec<i32>),
+    SetPrivateMode(Vec<i32>),   // DECSET，例如 "ESC [ ? 25 h"
+    ResetPrivateMode(Vec<i32>), // DECRST，例如 "ESC [ ? 25 l"
+    KeypadApplicationMode,
+    KeypadNumericMode,
+
     // AUX
     AuxOn,
     AuxOff,
 
+    // Control Strings (內容不含開頭與結尾)
+    OperatingSystemCommand(Vec<u8>),
+    DeviceControlString(Vec<u8>),
+    IgnoredString(Vec<u8>), // SOS、PM 與 APC
+
+    Reset,
+
     // Unknown
-    UnknownCsi(ParamList, u8), // parameter list, final byte
-    UnknownEscape(u8)
+    UnknownCsi(CsiSequence),
+    UnknownEscape(u8),
+    UnknownEscapeSequence(Vec<u8>, u8) // intermediate bytes, final byte，例如 "ESC ( B"
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum StringKind {
+    Osc,
+    Dcs,
+    Ignored
 }
 
 enum ProcessState {
     Text,
     Escaping,
-    CSI
+    EscapeIntermediate,
+    Csi,
+    ControlString(StringKind),
+    ControlStringEscape(StringKind) // 在字串中遇到 ESC，可能是 ST 的開頭
 }
 
 pub struct AnsiTokenizer {
     state: ProcessState,
-    csi_param: Vec<u8>
+    csi_param: Vec<u8>,
+    string_data: Vec<u8>
+}
+
+impl Default for AnsiTokenizer {
+    fn default() -> AnsiTokenizer {
+        AnsiTokenizer::new()
+    }
 }
 
 impl AnsiTokenizer {
     pub fn new() -> AnsiTokenizer {
         AnsiTokenizer {
             state: ProcessState::Text,
-            csi_param: vec![]
+            csi_param: vec![],
+            string_data: vec![]
         }
     }
 
-    pub fn tokenize<'a, 'b>(&'a mut self, bytes: &'b [u8]) -> Result<Vec<AnsiToken<'b>>, String> {
+    // 不會失敗：無法辨識的序列會變成 Unknown* token，CAN/SUB 會中斷正在處理的序列
+    pub fn tokenize<'b>(&mut self, bytes: &'b [u8]) -> Vec<AnsiToken<'b>> {
         let mut tokens = vec![];
         let mut text_start: usize = 0;
 
@@ -93,49 +167,67 @@ impl AnsiTokenizer {
             let byte = bytes[i];
             match self.state {
                 ProcessState::Text => {
+                    if byte == ESC {
+                        if i > text_start {
+                            tokens.push(AnsiToken::Text(&bytes[text_start .. i]));
+                        }
+                        self.state = ProcessState::Escaping;
+                    }
+                    continue;
+                },
+                ProcessState::Escaping | ProcessState::EscapeIntermediate | ProcessState::Csi => {
                     match byte {
+                        CAN | SUB => {
+                            self.abort();
+                        },
                         ESC => {
-                            if i > text_start {
-                                tokens.push(AnsiToken::Text(&bytes[text_start .. i]));
-                            }
+                            self.abort();
                             self.state = ProcessState::Escaping;
                         },
-                        _ => {}
+                        // 控制序列中的其他控制字元照常執行
+                        0 ..= 0x1F => {
+                            tokens.push(AnsiToken::Text(&bytes[i ..= i]));
+                        },
+                        DEL => {},
+                        0x80 ..= 0xFF => {
+                            // 不完整的序列，從這個 byte 開始當作一般文字
+                            self.abort();
+                            text_start = i;
+                            continue;
+                        },
+                        _ => {
+                            if let Some(token) = self.process_sequence_byte(byte) {
+                                tokens.push(token);
+                            }
+                        }
                     }
                 },
-                ProcessState::Escaping => {
+                ProcessState::ControlString(kind) => {
                     match byte {
-                        ESC_CSI => {
-                            self.state = ProcessState::CSI;
-                        },
-                        b => {
-                            tokens.push(AnsiToken::UnknownEscape(b));
-                            self.state = ProcessState::Text;
-                            text_start = i + 1;
-                        }
+                        CAN | SUB => self.abort(),
+                        BEL if kind == StringKind::Osc => tokens.push(self.finish_string(kind)),
+                        ESC => self.state = ProcessState::ControlStringEscape(kind),
+                        b => self.push_string_byte(b)
                     }
                 },
-                ProcessState::CSI => {
-                    if is_csi_final_byte(byte) {
-                        // Process the sequence
-                        let params = parse_csi_param(&self.csi_param);
-                        self.csi_param.clear();
-
-                        let token = match match_csi(byte, params) {
-                            Ok(t) => t,
-                            Err(msg) => return Err(msg)
-                        };
-                        tokens.push(token);
-
-                        self.state = ProcessState::Text;
-                        text_start = i + 1;
-                    } else if is_csi_param_or_interm_byte(byte) {
-                        self.csi_param.push(byte);
-                    } else {
-                        return Err(format!("Undefined byte occurred in control sequences: {}", byte));
+                ProcessState::ControlStringEscape(kind) => {
+                    match byte {
+                        ESC_ST => tokens.push(self.finish_string(kind)),
+                        CAN | SUB => self.abort(),
+                        b => {
+                            // 字串被新的控制序列打斷
+                            tokens.push(self.finish_string(kind));
+                            self.state = ProcessState::Escaping;
+                            if b != ESC {
+                                if let Some(token) = self.process_sequence_byte(b) {
+                                    tokens.push(token);
+                                }
+                            }
+                        }
                     }
                 }
             }
+            text_start = i + 1;
         }
 
         if let ProcessState::Text = self.state {
@@ -144,141 +236,260 @@ impl AnsiTokenizer {
             }
         }
 
-        Ok(tokens)
+        tokens
+    }
+
+    fn process_sequence_byte(&mut self, byte: u8) -> Option<AnsiToken<'static>> {
+        match self.state {
+            ProcessState::Escaping => {
+                self.state = ProcessState::Text;
+                match byte {
+                    ESC_CSI => self.state = ProcessState::Csi,
+                    ESC_OSC => self.state = ProcessState::ControlString(StringKind::Osc),
+                    ESC_DCS => self.state = ProcessState::ControlString(StringKind::Dcs),
+                    ESC_SOS | ESC_PM | ESC_APC => self.state = ProcessState::ControlString(StringKind::Ignored),
+                    0x20 ..= 0x2F => {
+                        self.csi_param.push(byte);
+                        self.state = ProcessState::EscapeIntermediate;
+                    },
+                    ESC_DECSC => return Some(AnsiToken::SaveCursorPos),
+                    ESC_DECRC => return Some(AnsiToken::RestoreCursorPos),
+                    ESC_IND => return Some(AnsiToken::Index),
+                    ESC_NEL => return Some(AnsiToken::NextLine),
+                    ESC_RI => return Some(AnsiToken::ReverseIndex),
+                    ESC_RIS => return Some(AnsiToken::Reset),
+                    ESC_DECKPAM => return Some(AnsiToken::KeypadApplicationMode),
+                    ESC_DECKPNM => return Some(AnsiToken::KeypadNumericMode),
+                    b => return Some(AnsiToken::UnknownEscape(b))
+                }
+                None
+            },
+            ProcessState::EscapeIntermediate => {
+                if (0x20 ..= 0x2F).contains(&byte) {
+                    if self.csi_param.len() < MAX_SEQUENCE_LEN {
+                        self.csi_param.push(byte);
+                    }
+                    None
+                } else {
+                    self.state = ProcessState::Text;
+                    let intermediates = std::mem::take(&mut self.csi_param);
+                    Some(AnsiToken::UnknownEscapeSequence(intermediates, byte))
+                }
+            },
+            ProcessState::Csi => {
+                if is_csi_final_byte(byte) {
+                    self.state = ProcessState::Text;
+                    let sequence = parse_csi(&std::mem::take(&mut self.csi_param), byte);
+                    Some(match_csi(sequence))
+                } else {
+                    if self.csi_param.len() < MAX_SEQUENCE_LEN {
+                        self.csi_param.push(byte);
+                    }
+                    None
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn push_string_byte(&mut self, byte: u8) {
+        if self.string_data.len() < MAX_SEQUENCE_LEN {
+            self.string_data.push(byte);
+        }
+    }
+
+    fn finish_string(&mut self, kind: StringKind) -> AnsiToken<'static> {
+        self.state = ProcessState::Text;
+        let data = std::mem::take(&mut self.string_data);
+        match kind {
+            StringKind::Osc => AnsiToken::OperatingSystemCommand(data),
+            StringKind::Dcs => AnsiToken::DeviceControlString(data),
+            StringKind::Ignored => AnsiToken::IgnoredString(data)
+        }
+    }
+
+    // 放棄目前的序列，回到一般文字
+    fn abort(&mut self) {
+        self.state = ProcessState::Text;
+        self.csi_param.clear();
+        self.string_data.clear();
     }
 }
 
-fn match_csi(final_byte: u8, params: ParamList) -> Result<AnsiToken<'static>, String> {
-    match final_byte {
-        CSI_CUU | CSI_CUD | CSI_CUF | CSI_CUB | CSI_CNL | CSI_CPL | CSI_CHA => {
-            let mut moves: isize = 1;
-            if params.len() == 1 {
-                moves = params[0].unwrap_or(1) as isize;
-            } else if params.len() > 1 {
-                return Err(format!("The parameter list is too long: {:?}", params));
-            }
+fn match_csi(sequence: CsiSequence) -> AnsiToken<'static> {
+    if !sequence.intermediates.is_empty() {
+        return AnsiToken::UnknownCsi(sequence);
+    }
 
-            match final_byte {
-                CSI_CUU => Ok(AnsiToken::CursorUp(moves)),
-                CSI_CUD => Ok(AnsiToken::CursorDown(moves)),
-                CSI_CUF => Ok(AnsiToken::CursorForward(moves)),
-                CSI_CUB => Ok(AnsiToken::CursorBack(moves)),
-                CSI_CNL => Ok(AnsiToken::CursorNextLine(moves)),
-                CSI_CPL => Ok(AnsiToken::CursorPreviousLine(moves)),
-                CSI_CHA => Ok(AnsiToken::CursorHoriAbs(moves)),
-                _ => Err(format!("It should not be here"))
-            }
-        },
-        CSI_CUP | CSI_HVP => {
-            let mut x: isize = 1;
-            let mut y: isize = 1;
-            if params.len() == 1 {
-                x = params[0].unwrap_or(1) as isize;
-            } else if params.len() == 2 {
-                x = params[0].unwrap_or(1) as isize;
-                y = params[1].unwrap_or(1) as isize;
-            } else if params.len() > 1 {
-                return Err(format!("The parameter list is too long: {:?}", params));
-            }
+    let params = &sequence.params;
+    // 第 n 個參數，省略時使用 default (多餘的參數會被忽略)
+    let param = |n: usize, default: i32| params.get(n).cloned().flatten().unwrap_or(default) as isize;
 
-            Ok(AnsiToken::CursorPosition(x, y))
-        },
-        CSI_ED | CSI_EL => {
-            let mut n = 0;
-            if params.len() == 1 {
-                n = params[0].unwrap_or(0);
-            } else if params.len() > 1 {
-                return Err(format!("The parameter list is too long: {:?}", params));
-            }
+    if let Some(prefix) = sequence.private {
+        let modes = params.iter().flatten().cloned().collect();
+        return match (prefix, sequence.final_byte) {
+            (b'?', CSI_SM) => AnsiToken::SetPrivateMode(modes),
+            (b'?', CSI_RM) => AnsiToken::ResetPrivateMode(modes),
+            _ => AnsiToken::UnknownCsi(sequence)
+        };
+    }
 
-            if final_byte == CSI_ED {
-                match n {
-                    0 => Ok(AnsiToken::EraseDisplay(EraseOption::EraseToEnd)),
-                    1 => Ok(AnsiToken::EraseDisplay(EraseOption::EraseToBeginning)),
-                    2 => Ok(AnsiToken::EraseDisplay(EraseOption::EraseEntire)),
-                    3 => Ok(AnsiToken::EraseDisplay(EraseOption::EraseEntireAndBuffer)),
-                    _ => Err(format!("Undefined parameter for erasing display: {}", n))
-                }
-            } else {
-                match n {
-                    0 => Ok(AnsiToken::EraseLine(EraseOption::EraseToEnd)),
-                    1 => Ok(AnsiToken::EraseLine(EraseOption::EraseToBeginning)),
-                    2 => Ok(AnsiToken::EraseLine(EraseOption::EraseEntire)),
-                    _ => Err(format!("Undefined parameter for erasing line: {}", n))
-                }
-            }
+    match sequence.final_byte {
+        CSI_CUU => AnsiToken::CursorUp(param(0, 1)),
+        CSI_CUD => AnsiToken::CursorDown(param(0, 1)),
+        CSI_CUF => AnsiToken::CursorForward(param(0, 1)),
+        CSI_CUB => AnsiToken::CursorBack(param(0, 1)),
+        CSI_CNL => AnsiToken::CursorNextLine(param(0, 1)),
+        CSI_CPL => AnsiToken::CursorPreviousLine(param(0, 1)),
+        CSI_CHA => AnsiToken::CursorHoriAbs(param(0, 1)),
+        CSI_CUP | CSI_HVP => AnsiToken::CursorPosition(param(0, 1), param(1, 1)),
+        CSI_ED => match param(0, 0) {
+            0 => AnsiToken::EraseDisplay(EraseOption::EraseToEnd),
+            1 => AnsiToken::EraseDisplay(EraseOption::EraseToBeginning),
+            2 => AnsiToken::EraseDisplay(EraseOption::EraseEntire),
+            3 => AnsiToken::EraseDisplay(EraseOption::EraseEntireAndBuffer),
+            _ => AnsiToken::UnknownCsi(sequence)
         },
-        CSI_SU | CSI_SD => {
-            let mut n: isize = 1;
-            if params.len() == 1 {
-                n = params[0].unwrap_or(1) as isize;
-            } else if params.len() > 1 {
-                return Err(format!("The parameter list is too long: {:?}", params));
-            }
-
-            match final_byte {
-                CSI_SU => Ok(AnsiToken::ScrollUp(n)),
-                CSI_SD => Ok(AnsiToken::ScrollDown(n)),
-                _ => Err(format!("It should not be here"))
-            }
+        CSI_EL => match param(0, 0) {
+            0 => AnsiToken::EraseLine(EraseOption::EraseToEnd),
+            1 => AnsiToken::EraseLine(EraseOption::EraseToBeginning),
+            2 => AnsiToken::EraseLine(EraseOption::EraseEntire),
+            _ => AnsiToken::UnknownCsi(sequence)
         },
+        CSI_SU => AnsiToken::ScrollUp(param(0, 1)),
+        CSI_SD => AnsiToken::ScrollDown(param(0, 1)),
+        CSI_DECSTBM => AnsiToken::SetScrollRegion(param(0, 0), param(1, 0)),
         CSI_SGR => {
-            if params.len() == 0 {
-                Ok(AnsiToken::ResetGraphic)
-            } else if params.len() == 1 {
-                let p = params[0].unwrap_or(0) as u8;
-
-                if p == 0 {
-                    Ok(AnsiToken::ResetGraphic)
-                } else {
-                    Ok(AnsiToken::SelectGraphic(vec![p]))
-                }
+            if params.iter().all(|p| p.unwrap_or(0) == 0) {
+                AnsiToken::ResetGraphic
             } else {
-                Ok(AnsiToken::SelectGraphic(
-                    params.iter().map(|p| p.unwrap_or(255) as u8).collect()))
+                AnsiToken::SelectGraphic(params.clone())
             }
         },
-        CSI_AUX => {
-            if params.len() == 1 {
-                match params[0].unwrap_or(0) {
-                    5 => Ok(AnsiToken::AuxOn),
-                    4 => Ok(AnsiToken::AuxOff),
-                    b => Err(format!("Unknown option for AUX port: {}", b))
-                }
-            } else {
-                Err(format!("The parameter list does not match: {:?}", params))
-            }
+        CSI_SM => AnsiToken::SetMode(params.iter().flatten().cloned().collect()),
+        CSI_RM => AnsiToken::ResetMode(params.iter().flatten().cloned().collect()),
+        CSI_AUX => match param(0, 0) {
+            5 => AnsiToken::AuxOn,
+            4 => AnsiToken::AuxOff,
+            _ => AnsiToken::UnknownCsi(sequence)
         },
-        CSI_SCP => Ok(AnsiToken::SaveCursorPos),
-        CSI_RCP => Ok(AnsiToken::RestoreCursorPos),
-        b => {
-            Ok(AnsiToken::UnknownCsi(params, b))
-        }
+        CSI_SCP => AnsiToken::SaveCursorPos,
+        CSI_RCP => AnsiToken::RestoreCursorPos,
+        _ => AnsiToken::UnknownCsi(sequence)
     }
 }
 
+fn is_csi_final_byte(byte: u8) -> bool {
+    (0x40 ..= 0x7E).contains(&byte)
+}
+
 // CSI Parameter Byte: 0x30–0x3F
 // CSI Intermidiate Byte: 0x20–0x2F
-fn is_csi_param_or_interm_byte(byte: u8) -> bool {
-    return 0x20 <= byte && byte <= 0x3F;
-}
+fn parse_csi(bytes: &[u8], final_byte: u8) -> CsiSequence {
+    let mut bytes = bytes;
+    let mut private = None;
+    if let Some(&b) = bytes.first() {
+        if (b'<' ..= b'?').contains(&b) {
+            private = Some(b);
+            bytes = &bytes[1 ..];
+        }
+    }
 
-fn is_csi_final_byte(byte: u8) -> bool {
-    return 0x40 <= byte && byte <= 0x7E;
+    let split = bytes.iter().position(|b| (0x20 ..= 0x2F).contains(b)).unwrap_or(bytes.len());
+    CsiSequence {
+        private,
+        params: parse_csi_param(&bytes[.. split]),
+        intermediates: bytes[split ..].to_vec(),
+        final_byte
+    }
 }
 
-// XXX: only handle "numbers" with "semicolon"
-fn parse_csi_param(byte: &[u8]) -> ParamList {
-    let mut result: ParamList = vec![];
-    let string = String::from_utf8_lossy(byte);
+// 數字以外的內容當作省略，太大的數字會被截斷
+fn parse_csi_param(bytes: &[u8]) -> ParamList {
+    if bytes.is_empty() {
+        return vec![];
+    }
 
-    for token in string.split(";") {
-        if token.len() == 0 {
-            result.push(None);
+    bytes.split(|b| *b == b';' || *b == b':').map(|token| {
+        if token.is_empty() || !token.iter().all(u8::is_ascii_digit) {
+            None
         } else {
-            result.push(Some(i32::from_str_radix(token, 10).expect("parse error")));
+            Some(token.iter().fold(0i32, |n, d| n.saturating_mul(10).saturating_add((d - b'0') as i32)))
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnsiToken, AnsiTokenizer, CsiSequence};
+
+    fn tokens(bytes: &[u8]) -> Vec<String> {
+        AnsiTokenizer::new().tokenize(bytes).iter().map(|t| format!("{:?}", t)).collect()
+    }
+
+    #[test]
+    fn private_modes_are_typed() {
+        assert_eq!(tokens(b"\x1b[?25l\x1b[?1049;25h"), vec!["ResetPrivateMode([25])", "SetPrivateMode([1049, 25])"]);
+        assert_eq!(tokens(b"\x1b[4h"), vec!["SetMode([4])"]);
+    }
+
+    #[test]
+    fn unknown_sequences_keep_their_parts() {
+        let mut tokenizer = AnsiTokenizer::new();
+        match tokenizer.tokenize(b"\x1b[>1;2c").pop() {
+            Some(AnsiToken::UnknownCsi(sequence)) => assert_eq!(sequence, CsiSequence {
+                private: Some(b'>'),
+                params: vec![Some(1), Some(2)],
+                intermediates: vec![],
+                final_byte: b'c'
+            }),
+            token => panic!("unexpected token: {:?}", token)
+        }
+        match tokenizer.tokenize(b"\x1b[2 q").pop() {
+            Some(AnsiToken::UnknownCsi(sequence)) => assert_eq!(sequence.intermediates, b" "),
+            token => panic!("unexpected token: {:?}", token)
         }
+        assert_eq!(tokens(b"\x1b(B"), vec!["UnknownEscapeSequence([40], 66)"]);
+    }
+
+    #[test]
+    fn malformed_parameters_do_not_abort() {
+        assert_eq!(tokens(b"\x1b[1<;99999999999H"), vec!["CursorPosition(1, 2147483647)"]);
+        assert_eq!(tokens(b"\x1b[5;6;7A"), vec!["CursorUp(5)"]);
+        assert_eq!(tokens(b"\x1b[9J"), vec![
+            "UnknownCsi(CsiSequence { private: None, params: [Some(9)], intermediates: [], final_byte: 74 })"]);
     }
 
-    result
+    #[test]
+    fn can_and_sub_abort_sequences() {
+        assert_eq!(tokens(b"\x1b[12\x18A\x1b]0;x\x1aB"), vec!["Text([65])", "Text([66])"]);
+    }
+
+    #[test]
+    fn control_bytes_inside_sequences_are_executed() {
+        assert_eq!(tokens(b"\x1b[1\r;2H"), vec!["Text([13])", "CursorPosition(1, 2)"]);
+    }
+
+    #[test]
+    fn control_strings_end_with_bel_or_st() {
+        assert_eq!(tokens(b"\x1b]0;title\x07A\x1bPdata\x1b\\B"), vec![
+            "OperatingSystemCommand([48, 59, 116, 105, 116, 108, 101])", "Text([65])",
+            "DeviceControlString([100, 97, 116, 97])", "Text([66])"]);
+    }
+
+    #[test]
+    fn two_character_escapes_are_typed() {
+        assert_eq!(tokens(b"\x1b7\x1b8\x1bD\x1bE\x1bM\x1bc\x1b=\x1b>\x1bZ"), vec![
+            "SaveCursorPos", "RestoreCursorPos", "Index", "NextLine", "ReverseIndex", "Reset",
+            "KeypadApplicationMode", "KeypadNumericMode", "UnknownEscape(90)"]);
+    }
+
+    #[test]
+    fn sequences_can_span_calls() {
+        let mut tokenizer = AnsiTokenizer::new();
+        assert!(tokenizer.tokenize(b"A\x1b[1").len() == 1);
+        assert_eq!(format!("{:?}", tokenizer.tokenize(b"0;2HB")), "[CursorPosition(10, 2), Text([66])]");
+        assert!(tokenizer.tokenize(b"\x1b]0;ti").is_empty());
+        assert_eq!(format!("{:?}", tokenizer.tokenize(b"tle\x07")), "[OperatingSystemCommand([48, 59, 116, 105, 116, 108, 101])]");
+    }
 }
@@ -0,0 +1,116 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+    #[default]
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Indexed(u8) // 亮色 (90-97) 與 256 色 (38;5;n)
+}
+
+impl Color {
+    fn from_basic(n: u8) -> Color {
+        match n {
+            0 => Color::Black,
+            1 => Color::Red,
+            2 => Color::Green,
+            3 => Color::Yellow,
+            4 => Color::Blue,
+            5 => Color::Magenta,
+            6 => Color::Cyan,
+            7 => Color::White,
+            n => Color::Indexed(n)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Attributes {
+    pub foreground: Color,
+    pub background: Color,
+    pub bold: bool,
+    pub blink: bool,
+    pub underline: bool,
+    pub reverse: bool
+}
+
+impl Attributes {
+    // 依序套用 SGR 參數，省略的參數視為 0
+    pub fn apply_sgr(&mut self, params: &[Option<i32>]) {
+        let mut iter = params.iter().map(|p| p.unwrap_or(0));
+        while let Some(p) = iter.next() {
+            match p {
+                0 => *self = Attributes::default(),
+                1 => self.bold = true,
+                4 => self.underline = true,
+                5 => self.blink = true,
+                7 => self.reverse = true,
+                22 => self.bold = false,
+                24 => self.underline = false,
+                25 => self.blink = false,
+                27 => self.reverse = false,
+                30 ..= 37 => self.foreground = Color::from_basic((p - 30) as u8),
+                39 => self.foreground = Color::Default,
+                40 ..= 47 => self.background = Color::from_basic((p - 40) as u8),
+                49 => self.background = Color::Default,
+                90 ..= 97 => self.foreground = Color::Indexed((p - 90 + 8) as u8),
+                100 ..= 107 => self.background = Color::Indexed((p - 100 + 8) as u8),
+                38 | 48 => {
+                    let color = match iter.next() {
+                        Some(5) => iter.next().and_then(|n| u8::try_from(n).ok()).map(Color::Indexed),
+                        // 不支援 RGB 色彩，略過三個參數
+                        Some(2) => {
+                            iter.by_ref().take(3).for_each(drop);
+                            None
+                        },
+                        _ => None
+                    };
+                    if let Some(color) = color {
+                        if p == 38 {
+                            self.foreground = color;
+                        } else {
+                            self.background = color;
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+// 雙位元組字的前後兩半各佔一格
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellKind {
+    Single,
+    Lead,
+    Trail
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub byte: u8,
+    pub attributes: Attributes,
+    pub kind: CellKind
+}
+
+impl Cell {
+    pub fn blank() -> Cell {
+        Cell {
+            byte: b' ',
+            attributes: Attributes::default(),
+            kind: CellKind::Single
+        }
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Cell {
+        Cell::blank()
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use encoding::all::BIG5_2003;
+use encoding::types::{Encoding, DecoderTrap, EncoderTrap};
+
+use crate::error::CrawlerError;
+
+// 內建的 UAO 對照表，格式與 uao250-b2u.big5.txt 相同 (每行 "0xBIG5 0xUNICODE")
+const UAO_TABLE: &str = include_str!("uao.txt");
+
+// 畫面與送出文字使用的編碼
+pub trait ScreenEncoding {
+    // 解碼一個字 (單 byte 或雙位元組)，無法解碼時回傳 None
+    fn decode_char(&self, bytes: &[u8]) -> Option<char>;
+
+    fn encode(&self, text: &str) -> Result<Vec<u8>, CrawlerError>;
+
+    // 無法解碼的 byte 會被略過
+    fn decode(&self, bytes: &[u8]) -> String {
+        let mut text = String::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let width = if bytes[i] >= 0x81 && i + 1 < bytes.len() { 2 } else { 1 };
+            if let Some(ch) = self.decode_char(&bytes[i .. i + width]) {
+                text.push(ch);
+            }
+            i += width;
+        }
+        text
+    }
+}
+
+// 標準的 Big5-2003
+pub struct Big5;
+
+impl ScreenEncoding for Big5 {
+    fn decode_char(&self, bytes: &[u8]) -> Option<char> {
+        if bytes.len() == 1 {
+            return Some(bytes[0] as char).filter(char::is_ascii);
+        }
+        let decoded = BIG5_2003.decode(bytes, DecoderTrap::Strict).ok()?;
+        let mut chars = decoded.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) if !ch.is_ascii() => Some(ch),
+            _ => None
+        }
+    }
+
+    fn encode(&self, text: &str) -> Result<Vec<u8>, CrawlerError> {
+        let mut bytes = vec![];
+        for ch in text.chars() {
+            match BIG5_2003.encode(ch.encode_utf8(&mut [0; 4]), EncoderTrap::Strict) {
+                Ok(encoded) => bytes.extend(encoded),
+                Err(e) => match big5_reverse_table().get(&ch) {
+                    Some(code) => bytes.extend_from_slice(&[(code >> 8) as u8, *code as u8]),
+                    None => return Err(CrawlerError::Decode(e.into_owned()))
+                }
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+// encoding 的 Big5 編碼表漏了幾十個常用字 (例如「者」、「都」)，
+// 編碼失敗時改從解碼的結果反查
+fn big5_reverse_table() -> &'static HashMap<char, u16> {
+    static TABLE: OnceLock<HashMap<char, u16>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = HashMap::new();
+        for lead in 0xA1 ..= 0xF9u8 {
+            for trail in (0x40 ..= 0x7Eu8).chain(0xA1 ..= 0xFE) {
+                if let Some(ch) = Big5.decode_char(&[lead, trail]) {
+                    table.entry(ch).or_insert((lead as u16) << 8 | trail as u16);
+                }
+            }
+        }
+        table
+    })
+}
+
+// PTT 使用的 Big5-UAO：先查 UAO 對照表，不在表中的字再當作 Big5 處理
+// 內建的對照表不完整 (見 uao.txt)，需要完整的 UAO 時用 with_table 載入
+pub struct Big5Uao {
+    to_unicode: HashMap<u16, char>,
+    to_big5: HashMap<char, u16>
+}
+
+impl Default for Big5Uao {
+    fn default() -> Big5Uao {
+        Big5Uao::new()
+    }
+}
+
+impl Big5Uao {
+    pub fn new() -> Big5Uao {
+        let mut encoding = Big5Uao {
+            to_unicode: HashMap::new(),
+            to_big5: HashMap::new()
+        };
+        encoding.add_table(UAO_TABLE).expect("the built-in UAO table is invalid");
+        encoding
+    }
+
+    // 在內建對照表之外再加上 table 中的對應 (例如完整的 uao250-b2u.big5.txt)
+    pub fn with_table(table: &str) -> Result<Big5Uao, CrawlerError> {
+        let mut encoding = Big5Uao::new();
+        encoding.add_table(table)?;
+        Ok(encoding)
+    }
+
+    fn add_table(&mut self, table: &str) -> Result<(), CrawlerError> {
+        for (n, line) in table.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mapping = parse_mapping(line)
+                .ok_or_else(|| CrawlerError::Decode(format!("UAO 對照表第 {} 行格式錯誤：{}", n + 1, line)))?;
+            self.to_unicode.insert(mapping.0, mapping.1);
+            self.to_big5.insert(mapping.1, mapping.0);
+        }
+        Ok(())
+    }
+}
+
+impl ScreenEncoding for Big5Uao {
+    fn decode_char(&self, bytes: &[u8]) -> Option<char> {
+        if bytes.len() == 2 {
+            let code = (bytes[0] as u16) << 8 | bytes[1] as u16;
+            if let Some(ch) = self.to_unicode.get(&code) {
+                return Some(*ch);
+            }
+            // 這些區段在 UAO 中另有用途 (例如簡體字)，不能當作 Big5-HKSCS 解碼，
+            // 對照表中沒有的字顯示成 U+FFFD，不要讓內容默默消失
+            if is_uao_only_lead(bytes[0]) {
+                return Some(char::REPLACEMENT_CHARACTER).filter(|_| is_trail(bytes[1]));
+            }
+        }
+        Big5.decode_char(bytes)
+    }
+
+    fn encode(&self, text: &str) -> Result<Vec<u8>, CrawlerError> {
+        let mut bytes = vec![];
+        for ch in text.chars() {
+            match self.to_big5.get(&ch) {
+                Some(code) => bytes.extend_from_slice(&[(code >> 8) as u8, *code as u8]),
+                None => bytes.extend(Big5.encode(ch.encode_utf8(&mut [0; 4]))?)
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+fn is_uao_only_lead(lead: u8) -> bool {
+    (0x81 ..= 0xA0).contains(&lead) || (0xFA ..= 0xFE).contains(&lead)
+}
+
+fn is_trail(byte: u8) -> bool {
+    (0x40 ..= 0x7E).contains(&byte) || (0xA1 ..= 0xFE).contains(&byte)
+}
+
+// "0xF9FE 0x2593" => (0xF9FE, '▓')
+fn parse_mapping(line: &str) -> Option<(u16, char)> {
+    let mut fields = line.split_whitespace();
+    let big5 = u16::from_str_radix(fields.next()?.trim_start_matches("0x"), 16).ok()?;
+    let unicode = u32::from_str_radix(fields.next()?.trim_start_matches("0x"), 16).ok()?;
+    Some((big5, char::from_u32(unicode)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Big5, Big5Uao, ScreenEncoding};
+
+    #[test]
+    fn uao_table_overrides_big5() {
+        let uao = Big5Uao::new();
+        assert_eq!(Big5.decode(b"\xf9\xfe"), "\u{ffed}");
+        assert_eq!(uao.decode(b"\xf9\xfe"), "▓");
+        assert_eq!(uao.encode("▓▓").unwrap(), b"\xf9\xfe\xf9\xfe");
+    }
+
+    #[test]
+    fn uao_falls_back_to_big5() {
+        let uao = Big5Uao::new();
+        assert_eq!(uao.decode(b"\xa4\xa4\xa4\xe5 ok \xc6\xe7"), "中文 ok ぁ");
+        assert_eq!(uao.encode("中文 ok").unwrap(), b"\xa4\xa4\xa4\xe5 ok");
+        assert!(uao.encode("\u{1f600}").is_err());
+    }
+
+    #[test]
+    fn big5_encodes_characters_missing_from_the_encoder() {
+        assert_eq!(Big5.encode("作者都起").unwrap(), b"\xa7\x40\xaa\xcc\xb3\xa3\xb0\x5f");
+        assert_eq!(Big5.decode(&Big5.encode("八卦都起來了").unwrap()), "八卦都起來了");
+    }
+
+    #[test]
+    fn extra_tables_extend_the_mapping() {
+        // 0x8140 在 Big5-HKSCS 中不是這個字，沒有對照表時不應該當作 HKSCS 解碼
+        assert_eq!(Big5Uao::new().decode(b"\x81\x40"), "\u{fffd}");
+
+        let uao = Big5Uao::with_table("# comment\n0x8140 0x4E28\n").unwrap();
+        assert_eq!(uao.decode(b"\x81\x40"), "丨");
+        assert_eq!(uao.encode("丨▓").unwrap(), b"\x81\x40\xf9\xfe");
+
+        assert!(Big5Uao::with_table("0x8140").is_err());
+    }
+
+    #[test]
+    fn unmapped_uao_codes_are_not_dropped() {
+        let uao = Big5Uao::new();
+        assert_eq!(uao.decode(b"a\x81\x40b\xfe\xfe"), "a\u{fffd}b\u{fffd}");
+        // 不是合法的 trail byte
+        assert_eq!(uao.decode(b"\x81\x30"), "");
+        assert!(uao.encode("\u{fffd}").is_err());
+    }
+}
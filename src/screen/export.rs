@@ -0,0 +1,281 @@
+use std::fmt::Write;
+
+use super::{Attributes, Color, Screen};
+
+// SVG 中每一格的大小 (px)
+const CELL_WIDTH: usize = 8;
+const CELL_HEIGHT: usize = 16;
+
+const HTML_STYLE: &str = "\
+pre.ptt-screen { background: #000; color: #aaa; font-family: monospace; line-height: 1; }
+.bold { font-weight: bold; }
+.underline { text-decoration: underline; }
+.blink { animation: blink 1s steps(1) infinite; }
+.cursor { outline: 1px solid #fff; }
+@keyframes blink { 50% { visibility: hidden; } }";
+
+// 同一列中屬性相同的一段連續文字
+struct Run {
+    column: usize,
+    width: usize,
+    attributes: Attributes,
+    cursor: bool,
+    text: String
+}
+
+impl Screen {
+    // 可以直接輸出到終端機重播的 ANSI 文字 (UTF-8)，最後會把游標移回原本的位置
+    pub fn to_ansi(&self) -> String {
+        let mut output = String::from("\x1b[m\x1b[2J\x1b[1;1H");
+        for r in 0 .. self.height {
+            for run in self.row_runs(r) {
+                let _ = write!(output, "\x1b[{}m{}", sgr_params(&run.attributes).join(";"), run.text);
+            }
+            output.push_str("\x1b[m");
+            if r + 1 < self.height {
+                output.push_str("\r\n");
+            }
+        }
+        let _ = write!(output, "\x1b[{};{}H", self.cursor.0 + 1, self.cursor.1 + 1);
+        output
+    }
+
+    // 獨立的 HTML 文件，用 CSS class 表示顏色與屬性
+    pub fn to_html(&self) -> String {
+        let mut output = String::new();
+        let _ = write!(output, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+            <style>\n{}\n{}</style>\n</head>\n<body>\n<pre class=\"ptt-screen\">", HTML_STYLE, color_classes());
+        for r in 0 .. self.height {
+            for run in self.row_runs(r) {
+                let (foreground, background) = displayed_colors(&run.attributes);
+                let mut classes = vec![format!("f{}", color_index(foreground).unwrap_or(7))];
+                if let Some(index) = color_index(background) {
+                    classes.push(format!("b{}", index));
+                }
+                if run.attributes.bold {
+                    classes.push("bold".to_string());
+                }
+                if run.attributes.underline {
+                    classes.push("underline".to_string());
+                }
+                if run.attributes.blink {
+                    classes.push("blink".to_string());
+                }
+                if run.cursor {
+                    classes.push("cursor".to_string());
+                }
+                let _ = write!(output, "<span class=\"{}\">{}</span>", classes.join(" "), escape_xml(&run.text));
+            }
+            output.push('\n');
+        }
+        output.push_str("</pre>\n</body>\n</html>\n");
+        output
+    }
+
+    // SVG 圖片，游標以白色外框標示
+    pub fn to_svg(&self) -> String {
+        let (width, height) = (self.width * CELL_WIDTH, self.height * CELL_HEIGHT);
+        let mut output = String::new();
+        let _ = write!(output, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+            font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">\n\
+            <rect width=\"100%\" height=\"100%\" fill=\"#000\"/>\n", width, height, CELL_HEIGHT - 2);
+        for r in 0 .. self.height {
+            let y = r * CELL_HEIGHT;
+            for run in self.row_runs(r) {
+                let x = run.column * CELL_WIDTH;
+                let (foreground, background) = displayed_colors(&run.attributes);
+                if let Some(index) = color_index(background) {
+                    let _ = writeln!(output, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                        x, y, run.width * CELL_WIDTH, CELL_HEIGHT, rgb(index));
+                }
+                if run.text.trim().is_empty() {
+                    continue;
+                }
+                let mut style = String::new();
+                if run.attributes.bold {
+                    style.push_str(" font-weight=\"bold\"");
+                }
+                if run.attributes.underline {
+                    style.push_str(" text-decoration=\"underline\"");
+                }
+                let _ = writeln!(output, "<text x=\"{}\" y=\"{}\" textLength=\"{}\" fill=\"{}\"{}>{}</text>",
+                    x, y + CELL_HEIGHT - 3, run.width * CELL_WIDTH, rgb(color_index(foreground).unwrap_or(7)),
+                    style, escape_xml(&run.text));
+            }
+        }
+        let _ = writeln!(output, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#fff\"/>",
+            self.cursor.1 * CELL_WIDTH, self.cursor.0 * CELL_HEIGHT, CELL_WIDTH, CELL_HEIGHT);
+        output.push_str("</svg>\n");
+        output
+    }
+
+    // 把一列切成屬性相同的片段，雙位元組的字以前半格的屬性為準
+    fn row_runs(&self, r: usize) -> Vec<Run> {
+        let mut runs: Vec<Run> = vec![];
+        let mut c = 0;
+        while c < self.width {
+            let attributes = self.lines[r][c].attributes;
+            let (ch, width) = self.glyph_at(r, c);
+            let text = ch.to_string();
+            let cursor = self.cursor.0 == r && (c .. c + width).contains(&self.cursor.1);
+
+            match runs.last_mut() {
+                Some(ref mut run) if run.attributes == attributes && run.cursor == cursor => {
+                    run.width += width;
+                    run.text.push_str(&text);
+                },
+                _ => runs.push(Run {
+                    column: c,
+                    width,
+                    attributes,
+                    cursor,
+                    text
+                })
+            }
+            c += width;
+        }
+        runs
+    }
+}
+
+fn sgr_params(attributes: &Attributes) -> Vec<String> {
+    let mut params = vec!["0".to_string()];
+    if attributes.bold {
+        params.push("1".to_string());
+    }
+    if attributes.underline {
+        params.push("4".to_string());
+    }
+    if attributes.blink {
+        params.push("5".to_string());
+    }
+    if attributes.reverse {
+        params.push("7".to_string());
+    }
+    if let Some(index) = color_index(attributes.foreground) {
+        params.push(match index {
+            0 ..= 7 => format!("{}", 30 + index),
+            _ => format!("38;5;{}", index)
+        });
+    }
+    if let Some(index) = color_index(attributes.background) {
+        params.push(match index {
+            0 ..= 7 => format!("{}", 40 + index),
+            _ => format!("48;5;{}", index)
+        });
+    }
+    params
+}
+
+// 套用反白與粗體 (亮色) 之後實際顯示的前景與背景色
+fn displayed_colors(attributes: &Attributes) -> (Color, Color) {
+    let (mut foreground, mut background) = (attributes.foreground, attributes.background);
+    if attributes.reverse {
+        foreground = match background {
+            Color::Default => Color::Black,
+            color => color
+        };
+        background = match attributes.foreground {
+            Color::Default => Color::White,
+            color => color
+        };
+    }
+    if attributes.bold {
+        if let Some(index @ 0 ..= 7) = color_index(foreground) {
+            foreground = Color::Indexed(index + 8);
+        } else if foreground == Color::Default {
+            foreground = Color::Indexed(15);
+        }
+    }
+    (foreground, background)
+}
+
+fn color_index(color: Color) -> Option<u8> {
+    match color {
+        Color::Default => None,
+        Color::Black => Some(0),
+        Color::Red => Some(1),
+        Color::Green => Some(2),
+        Color::Yellow => Some(3),
+        Color::Blue => Some(4),
+        Color::Magenta => Some(5),
+        Color::Cyan => Some(6),
+        Color::White => Some(7),
+        Color::Indexed(index) => Some(index)
+    }
+}
+
+// xterm 的 256 色調色盤
+fn rgb(index: u8) -> String {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0), (170, 0, 0), (0, 170, 0), (170, 85, 0),
+        (0, 0, 170), (170, 0, 170), (0, 170, 170), (170, 170, 170),
+        (85, 85, 85), (255, 85, 85), (85, 255, 85), (255, 255, 85),
+        (85, 85, 255), (255, 85, 255), (85, 255, 255), (255, 255, 255)
+    ];
+    let (r, g, b) = match index {
+        0 ..= 15 => BASIC[index as usize],
+        16 ..= 231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        },
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn color_classes() -> String {
+    let mut classes = String::new();
+    for index in 0 ..= 255u8 {
+        let _ = writeln!(classes, ".f{0} {{ color: {1}; }} .b{0} {{ background: {1}; }}", index, rgb(index));
+    }
+    classes
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Screen;
+
+    fn screen_after(bytes: &[u8]) -> Screen {
+        let mut screen = Screen::with_size(20, 3);
+        screen.process(bytes);
+        screen
+    }
+
+    #[test]
+    fn ansi_export_replays_to_the_same_screen() {
+        let screen = screen_after(b"plain \x1b[1;31mred\x1b[m\r\n\x1b[44;37mblue\x1b[m\x1b[3;5H");
+        let mut replayed = Screen::with_size(20, 3);
+        replayed.process(screen.to_ansi().as_bytes());
+
+        assert_eq!(replayed.snapshot(), screen.snapshot());
+        assert_eq!(replayed.get_cursor_position(), (2, 4));
+        for c in 0 .. 20 {
+            assert_eq!(replayed.attributes_at(0, c), screen.attributes_at(0, c));
+            assert_eq!(replayed.attributes_at(1, c), screen.attributes_at(1, c));
+        }
+    }
+
+    #[test]
+    fn html_export_marks_colors_and_cursor() {
+        let html = screen_after(b"<a>\x1b[1;31m\xc3\x7a\x1b[m").to_html();
+        assert!(html.contains("<span class=\"f7\">&lt;a&gt;</span><span class=\"f9 bold\">爆</span>"));
+        assert!(html.contains("<span class=\"f7 cursor\"> </span>"));
+    }
+
+    #[test]
+    fn svg_export_draws_backgrounds_and_cursor() {
+        let svg = screen_after(b"\x1b[2;3H\x1b[42mok").to_svg();
+        assert!(svg.contains("<rect x=\"16\" y=\"16\" width=\"16\" height=\"16\" fill=\"#00aa00\"/>"));
+        assert!(svg.contains(">ok</text>"));
+        assert!(svg.contains("<rect x=\"32\" y=\"16\" width=\"8\" height=\"16\" fill=\"none\" stroke=\"#fff\"/>"));
+    }
+}
@@ -1,37 +1,113 @@
 
-mod ansi;
+use std::collections::VecDeque;
 
-use encoding::all::BIG5_2003;
-use encoding::types::{Encoding, DecoderTrap};
+pub mod ansi;
+mod cell;
+mod charset;
+mod export;
 
 use self::ansi::{AnsiTokenizer, AnsiToken, EraseOption};
 
+pub use self::cell::{Attributes, Cell, CellKind, Color};
+pub use self::charset::{Big5, Big5Uao, ScreenEncoding};
+
 const SCREEN_HEIGHT: usize = 24;
 const SCREEN_WIDTH: usize = 80;
 
-const SPACE_BYTE: u8 = 32;
+// Big5 雙位元組字的第一個 byte 範圍
+const BIG5_LEAD: (u8, u8) = (0x81, 0xFE);
+
+// 一列的文字，columns[i] 是第 i 個字元在畫面上的起始欄位
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowText {
+    pub text: String,
+    pub columns: Vec<usize>
+}
+
+impl RowText {
+    // 例如 text.find() 得到的 byte 位置所對應的欄位
+    pub fn column_of(&self, byte_index: usize) -> Option<usize> {
+        self.text.char_indices().position(|(i, _)| i == byte_index).map(|n| self.columns[n])
+    }
+}
 
 pub struct Screen {
     tokenizer: AnsiTokenizer,
-    lines: [[u8; SCREEN_WIDTH]; SCREEN_HEIGHT],
-    cursor: (usize, usize) // (r, c)
+    width: usize,
+    height: usize,
+    lines: Vec<Vec<Cell>>,
+    cursor: (usize, usize), // (r, c)
+    saved_cursor: (usize, usize),
+    attributes: Attributes, // 之後輸入的文字會套用的屬性
+    pending_lead: Option<(usize, usize)>, // 還在等後半個 byte 的雙位元組字
+    encoding: Box<dyn ScreenEncoding>,
+    scroll_region: (usize, usize), // (top, bottom)，包含 bottom
+    scrollback: VecDeque<String>,
+    scrollback_limit: usize
+}
+
+impl Default for Screen {
+    fn default() -> Screen {
+        Screen::new()
+    }
 }
 
 impl Screen {
     pub fn new() -> Screen {
+        Screen::with_size(SCREEN_WIDTH, SCREEN_HEIGHT)
+    }
+
+    // 寬高都至少要 1，否則會 panic
+    pub fn with_size(width: usize, height: usize) -> Screen {
+        assert!(width > 0 && height > 0, "screen size must be at least 1x1, got {}x{}", width, height);
         Screen {
             tokenizer: AnsiTokenizer::new(),
-            lines: [[SPACE_BYTE; SCREEN_WIDTH]; SCREEN_HEIGHT],
-            cursor: (0, 0)
+            width,
+            height,
+            lines: vec![vec![Cell::blank(); width]; height],
+            cursor: (0, 0),
+            saved_cursor: (0, 0),
+            attributes: Attributes::default(),
+            pending_lead: None,
+            encoding: Box::new(Big5Uao::new()),
+            scroll_region: (0, height - 1),
+            scrollback: VecDeque::new(),
+            scrollback_limit: 0
         }
     }
 
-    pub fn process(&mut self, bytes: &[u8]) {
-        // println!("Data: {:?}", bytes);
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // 預設為 Big5-UAO
+    pub fn set_encoding(&mut self, encoding: Box<dyn ScreenEncoding>) {
+        self.encoding = encoding;
+    }
+
+    pub fn get_encoding(&self) -> &dyn ScreenEncoding {
+        &*self.encoding
+    }
+
+    // 從畫面頂端捲出去的行最多保留 limit 行，0 表示不保留 (預設)
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        self.scrollback_limit = limit;
+        while self.scrollback.len() > limit {
+            self.scrollback.pop_front();
+        }
+    }
 
-        for token in self.tokenizer.tokenize(bytes).unwrap() {
-            // println!("Tokens: {:?}", token);
+    // 取出目前保留的行 (由舊到新)，並清空 scrollback
+    pub fn take_scrollback(&mut self) -> Vec<String> {
+        self.scrollback.drain(..).collect()
+    }
 
+    pub fn process(&mut self, bytes: &[u8]) {
+        for token in self.tokenizer.tokenize(bytes) {
             match token {
                 AnsiToken::Text(text_bytes) => {
                     for b in text_bytes {
@@ -41,46 +117,94 @@ impl Screen {
                             self.type_in(*b);
                         }
                     }
-
-                    // println!("===== Print Screen =====");
-                    // self.print_screen();
                 },
-                AnsiToken::SelectGraphic(_) | AnsiToken::ResetGraphic => {}, // Ignored
+                AnsiToken::SelectGraphic(params) => {
+                    self.attributes.apply_sgr(&params);
+                },
+                AnsiToken::ResetGraphic => {
+                    self.attributes = Attributes::default();
+                },
+                AnsiToken::CursorUp(n) => {
+                    self.cursor.0 = self.cursor.0.saturating_sub(moves(n));
+                },
+                AnsiToken::CursorDown(n) => {
+                    self.cursor.0 = (self.cursor.0 + moves(n)).min(self.height - 1);
+                },
+                AnsiToken::CursorForward(n) => {
+                    self.cursor.1 = (self.cursor.1 + moves(n)).min(self.width - 1);
+                },
+                AnsiToken::CursorBack(n) => {
+                    self.cursor.1 = self.cursor.1.saturating_sub(moves(n));
+                },
+                AnsiToken::CursorNextLine(n) => {
+                    self.cursor.0 = (self.cursor.0 + moves(n)).min(self.height - 1);
+                    self.cursor.1 = 0;
+                },
+                AnsiToken::CursorPreviousLine(n) => {
+                    self.cursor.0 = self.cursor.0.saturating_sub(moves(n));
+                    self.cursor.1 = 0;
+                },
+                AnsiToken::CursorHoriAbs(c) => {
+                    self.cursor.1 = (moves(c) - 1).min(self.width - 1);
+                },
                 AnsiToken::CursorPosition(r, c) => {
-                    self.cursor.0 = (r - 1) as usize;
-                    self.cursor.1 = (c - 1) as usize;
+                    self.cursor.0 = (moves(r) - 1).min(self.height - 1);
+                    self.cursor.1 = (moves(c) - 1).min(self.width - 1);
+                },
+                AnsiToken::SaveCursorPos => {
+                    self.saved_cursor = self.cursor;
+                },
+                AnsiToken::RestoreCursorPos => {
+                    self.cursor = self.saved_cursor;
+                },
+                AnsiToken::ScrollUp(n) => {
+                    self.scroll_up(moves(n));
+                },
+                AnsiToken::ScrollDown(n) => {
+                    self.scroll_down(moves(n));
+                },
+                AnsiToken::SetScrollRegion(top, bottom) => {
+                    let top = moves(top) - 1;
+                    let bottom = if bottom <= 0 { self.height - 1 } else { (bottom as usize - 1).min(self.height - 1) };
+                    // 不合理的範圍就恢復成整個畫面
+                    self.scroll_region = if top < bottom { (top, bottom) } else { (0, self.height - 1) };
+                    self.cursor = (0, 0);
+                },
+                AnsiToken::Index => {
+                    self.line_feed();
+                },
+                AnsiToken::NextLine => {
+                    self.cursor.1 = 0;
+                    self.line_feed();
+                },
+                AnsiToken::ReverseIndex => {
+                    self.reverse_line_feed();
                 },
                 AnsiToken::EraseDisplay(opt) => {
-                    match opt {
-                        EraseOption::EraseEntire => {
-                            self.erase_screen();
-                        },
-                        _ => {
-                            println!("Unhandled EraseDisplay Operation: {:?}", opt);
-                        }
-                    }
+                    self.erase_display(opt);
                 },
                 AnsiToken::EraseLine(opt) => {
-                    match opt {
-                        EraseOption::EraseToEnd => {
-                            self.erase_to_end(true);
-                        },
-                        _ => {
-                            println!("Unhandled EraseLine Operation: {:?}", opt);
-                        }
-                    }
+                    self.erase_line(opt);
+                },
+                AnsiToken::Reset => {
+                    self.reset();
                 },
+                // 不影響畫面內容
+                AnsiToken::SetMode(_) | AnsiToken::ResetMode(_)
+                    | AnsiToken::SetPrivateMode(_) | AnsiToken::ResetPrivateMode(_)
+                    | AnsiToken::KeypadApplicationMode | AnsiToken::KeypadNumericMode
+                    | AnsiToken::OperatingSystemCommand(_) | AnsiToken::DeviceControlString(_)
+                    | AnsiToken::IgnoredString(_) => {},
                 _ => {
-                    println!("Unhandled Escaped Code: {:?}", token);
+                    log::debug!("Unhandled Escaped Code: {:?}", token);
                 }
             }
         }
     }
 
     pub fn check_string(&self, pattern: &str) -> bool {
-        for i in 0 .. SCREEN_HEIGHT {
-            let s = BIG5_2003.decode(&self.lines[i][..], DecoderTrap::Ignore).expect("Big5 解碼錯誤");
-            if let Some(_) = s.find(pattern) {
+        for i in 0 .. self.height {
+            if self.row_text(i).contains(pattern) {
                 return true;
             }
         }
@@ -88,77 +212,501 @@ impl Screen {
         false
     }
 
+    pub fn row_text(&self, rid: usize) -> String {
+        self.row_text_columns(rid).text
+    }
+
+    // 破損的半個字會變成空白，所以字元不會因此位移
+    pub fn row_text_columns(&self, rid: usize) -> RowText {
+        let mut text = String::new();
+        let mut columns = vec![];
+        let mut c = 0;
+        while c < self.width {
+            let (ch, width) = self.glyph_at(rid, c);
+            text.push(ch);
+            columns.push(c);
+            c += width;
+        }
+        RowText {
+            text,
+            columns
+        }
+    }
+
+    // 破損的半個字會換成空白
+    pub fn row_bytes(&self, rid: usize) -> Vec<u8> {
+        (0 .. self.width).map(|c| {
+            if self.is_broken(rid, c) {
+                b' '
+            } else {
+                self.lines[rid][c].byte
+            }
+        }).collect()
+    }
+
+    // 雙位元組字只剩下一半 (另一半被覆蓋或沒有寫入)
+    pub fn is_broken(&self, r: usize, c: usize) -> bool {
+        let line = &self.lines[r];
+        match line[c].kind {
+            CellKind::Single => line[c].byte >= BIG5_LEAD.0,
+            CellKind::Lead => c + 1 >= self.width || line[c + 1].kind != CellKind::Trail,
+            CellKind::Trail => c == 0 || line[c - 1].kind != CellKind::Lead
+        }
+    }
+
+    pub fn cell(&self, r: usize, c: usize) -> Cell {
+        self.lines[r][c]
+    }
+
+    pub fn attributes_at(&self, r: usize, c: usize) -> Attributes {
+        self.lines[r][c].attributes
+    }
+
+    pub fn snapshot(&self) -> String {
+        (0 .. self.height).map(|i| self.row_text(i)).collect::<Vec<String>>().join("\n")
+    }
+
     pub fn get_cursor_position(&self) -> (usize, usize) {
-        self.cursor.clone()
+        self.cursor
+    }
+
+    pub fn get_attributes(&self) -> Attributes {
+        self.attributes
+    }
+
+    // 回傳 (r, c) 顯示的字元與它佔的欄位數，無法顯示的部分都當作一格空白
+    fn glyph_at(&self, r: usize, c: usize) -> (char, usize) {
+        let cell = self.lines[r][c];
+        if cell.kind == CellKind::Lead && !self.is_broken(r, c) {
+            if let Some(ch) = self.encoding.decode_char(&[cell.byte, self.lines[r][c + 1].byte]) {
+                return (ch, 2);
+            }
+        }
+        if cell.kind == CellKind::Single && cell.byte.is_ascii() {
+            (cell.byte as char, 1)
+        } else {
+            (' ', 1)
+        }
     }
 
     fn backspace(&mut self) {
-        self.lines[self.cursor.0][self.cursor.1] = SPACE_BYTE;
+        if self.cursor.1 >= self.width {
+            self.cursor.1 = self.width - 1;
+        }
+        self.lines[self.cursor.0][self.cursor.1] = Cell::blank();
         if self.cursor.1 > 0 {
             self.cursor.1 -= 1;
         }
     }
 
     fn type_in(&mut self, byte: u8) {
-        self.lines[self.cursor.0][self.cursor.1] = byte;
+        // 超過最後一欄時換到下一列
+        if self.cursor.1 >= self.width {
+            self.cursor.1 = 0;
+            self.line_feed();
+        }
+
+        // 緊接在前半個 byte 之後寫入的就是後半個 byte，即使中間穿插了 SGR
+        let (r, c) = self.cursor;
+        let follows_lead = c > 0 && self.pending_lead == Some((r, c - 1))
+            && self.lines[r][c - 1].kind == CellKind::Lead;
+        let kind = if follows_lead {
+            CellKind::Trail
+        } else if (BIG5_LEAD.0 ..= BIG5_LEAD.1).contains(&byte) {
+            CellKind::Lead
+        } else {
+            CellKind::Single
+        };
+        self.pending_lead = if kind == CellKind::Lead { Some((r, c)) } else { None };
+
+        self.lines[r][c] = Cell {
+            byte,
+            attributes: self.attributes,
+            kind
+        };
         self.cursor.1 += 1;
     }
 
     fn handle_control_byte(&mut self, byte: u8) {
         match byte {
-            0 => {  // NUL: Null (\0)
-                println!("'\\0' (Null) appears");
-            },
-            7 => { // BEL: BELL (\a)
+            0 | 7 => { // NUL: Null (\0)、BEL: BELL (\a)
                 // Do nothing
             },
             8 => {  // BS: Backspace (\b)
                 self.backspace();
             },
             10 => { // LF: Line Feed (\n)
-                self.cursor.0 += 1;
+                self.line_feed();
             },
             13 => { // CR: Carriage Return (\r)
                 self.cursor.1 = 0;
             },
+            24 | 26 => { // CAN, SUB: 只用來中斷控制序列
+                // Do nothing
+            },
             _ => {
-                println!("Unhandled Control Byte: {:?}", byte);
+                log::debug!("Unhandled Control Byte: {:?}", byte);
             }
         }
     }
 
-    pub fn print_screen(&self) {
-        println!("==============================================================================");
-        for i in 0 .. SCREEN_HEIGHT {
-            println!("{:02}| {}", i + 1, BIG5_2003.decode(&self.lines[i][..], DecoderTrap::Ignore).expect("Big5 解碼錯誤"));
+    // ESC c: 回到剛連線時的狀態 (scrollback 與編碼設定不變)
+    fn reset(&mut self) {
+        self.erase_screen();
+        self.cursor = (0, 0);
+        self.saved_cursor = (0, 0);
+        self.attributes = Attributes::default();
+        self.pending_lead = None;
+        self.scroll_region = (0, self.height - 1);
+    }
+
+    // 在捲動範圍的最底下換行時會把範圍內的內容往上捲
+    fn line_feed(&mut self) {
+        if self.cursor.0 == self.scroll_region.1 {
+            self.scroll_up(1);
+        } else if self.cursor.0 + 1 < self.height {
+            self.cursor.0 += 1;
         }
-        println!("==============================================================================");
     }
 
-    fn erase_to_end(&mut self, only_line: bool) {
-        for i in self.cursor.1 .. SCREEN_WIDTH {
-            self.lines[self.cursor.0][i] = SPACE_BYTE;
+    fn reverse_line_feed(&mut self) {
+        if self.cursor.0 == self.scroll_region.0 {
+            self.scroll_down(1);
+        } else if self.cursor.0 > 0 {
+            self.cursor.0 -= 1;
         }
+    }
 
-        // Erase the area below the current line
-        if !only_line {
-            for i in self.cursor.0 + 1 .. SCREEN_HEIGHT {
-                for j in 0 .. SCREEN_WIDTH {
-                    self.lines[i][j] = SPACE_BYTE;
+    fn scroll_up(&mut self, n: usize) {
+        let (top, bottom) = self.scroll_region;
+        for _ in 0 .. n.min(bottom - top + 1) {
+            if top == 0 && self.scrollback_limit > 0 {
+                if self.scrollback.len() >= self.scrollback_limit {
+                    self.scrollback.pop_front();
                 }
+                let text = self.row_text(0);
+                self.scrollback.push_back(text);
+            }
+            self.lines.remove(top);
+            self.lines.insert(bottom, vec![Cell::blank(); self.width]);
+        }
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        let (top, bottom) = self.scroll_region;
+        for _ in 0 .. n.min(bottom - top + 1) {
+            self.lines.remove(bottom);
+            self.lines.insert(top, vec![Cell::blank(); self.width]);
+        }
+    }
+
+    fn erase_line(&mut self, opt: EraseOption) {
+        // 游標可能停在最後一欄之後 (等待換行)
+        let column = self.cursor.1;
+        let range = match opt {
+            EraseOption::EraseToEnd => column .. self.width,
+            EraseOption::EraseToBeginning => 0 .. (column + 1).min(self.width),
+            EraseOption::EraseEntire | EraseOption::EraseEntireAndBuffer => 0 .. self.width
+        };
+        for cell in self.lines[self.cursor.0][range].iter_mut() {
+            *cell = Cell::blank();
+        }
+    }
+
+    fn erase_display(&mut self, opt: EraseOption) {
+        let rows = match opt {
+            EraseOption::EraseToEnd => {
+                self.erase_line(EraseOption::EraseToEnd);
+                self.cursor.0 + 1 .. self.height
+            },
+            EraseOption::EraseToBeginning => {
+                self.erase_line(EraseOption::EraseToBeginning);
+                0 .. self.cursor.0
+            },
+            EraseOption::EraseEntire => 0 .. self.height,
+            EraseOption::EraseEntireAndBuffer => {
+                self.scrollback.clear();
+                0 .. self.height
+            }
+        };
+        for line in self.lines[rows].iter_mut() {
+            for cell in line.iter_mut() {
+                *cell = Cell::blank();
             }
         }
     }
 
     fn erase_screen(&mut self) {
-        for i in 0 .. SCREEN_HEIGHT {
-            for j in 0 .. SCREEN_WIDTH {
-                self.lines[i][j] = SPACE_BYTE;
+        for line in self.lines.iter_mut() {
+            for cell in line.iter_mut() {
+                *cell = Cell::blank();
             }
         }
     }
 }
 
+// 參數為 0 或省略時都當作 1
+fn moves(n: isize) -> usize {
+    n.max(1) as usize
+}
+
 fn is_control(byte: u8) -> bool {
-    return byte < 32;
+    byte < 32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Attributes, Color, RowText, Screen};
+
+    fn screen_after(bytes: &[u8]) -> Screen {
+        let mut screen = Screen::new();
+        screen.process(bytes);
+        screen
+    }
+
+    #[test]
+    fn cursor_moves_relatively() {
+        let screen = screen_after(b"\x1b[10;10H\x1b[2A\x1b[3C\x1b[B\x1b[5D");
+        assert_eq!(screen.get_cursor_position(), (8, 7));
+    }
+
+    #[test]
+    fn cursor_moves_are_clamped_to_the_screen() {
+        let screen = screen_after(b"\x1b[5;5H\x1b[99A\x1b[99D");
+        assert_eq!(screen.get_cursor_position(), (0, 0));
+
+        let screen = screen_after(b"\x1b[99B\x1b[99C");
+        assert_eq!(screen.get_cursor_position(), (23, 79));
+
+        let screen = screen_after(b"\x1b[0;0H\x1b[30;100H");
+        assert_eq!(screen.get_cursor_position(), (23, 79));
+    }
+
+    #[test]
+    fn zero_or_missing_parameters_move_one_cell() {
+        let screen = screen_after(b"\x1b[5;5H\x1b[0A\x1b[C");
+        assert_eq!(screen.get_cursor_position(), (3, 5));
+    }
+
+    #[test]
+    fn next_and_previous_line_return_to_first_column() {
+        let screen = screen_after(b"\x1b[5;5H\x1b[2E");
+        assert_eq!(screen.get_cursor_position(), (6, 0));
+
+        let screen = screen_after(b"\x1b[5;5H\x1b[3F");
+        assert_eq!(screen.get_cursor_position(), (1, 0));
+    }
+
+    #[test]
+    fn horizontal_absolute_keeps_the_row() {
+        let screen = screen_after(b"\x1b[5;5H\x1b[20G");
+        assert_eq!(screen.get_cursor_position(), (4, 19));
+    }
+
+    #[test]
+    fn save_and_restore_cursor() {
+        let screen = screen_after(b"\x1b[3;4H\x1b[s\x1b[20;20HX\x1b[uY");
+        assert_eq!(screen.get_cursor_position(), (2, 4));
+        assert_eq!(&screen.row_text(2)[.. 4], "   Y");
+        assert_eq!(&screen.row_text(19)[.. 20], "                   X");
+    }
+
+    #[test]
+    fn relative_moves_write_to_the_right_cells() {
+        let screen = screen_after(b"ABC\x1b[2DX\x1b[BY\x1b[AZ");
+        assert_eq!(&screen.row_text(0)[.. 5], "AXCZ ");
+        assert_eq!(&screen.row_text(1)[.. 4], "  Y ");
+    }
+
+    #[test]
+    fn text_carries_the_current_graphic_attributes() {
+        let screen = screen_after(b"A\x1b[1;31;44mB\x1b[5;7mC\x1b[mD");
+        assert_eq!(screen.attributes_at(0, 0), Attributes::default());
+
+        let b = screen.cell(0, 1);
+        assert_eq!(b.byte, b'B');
+        assert_eq!((b.attributes.foreground, b.attributes.background), (Color::Red, Color::Blue));
+        assert!(b.attributes.bold && !b.attributes.blink);
+
+        let c = screen.attributes_at(0, 2);
+        assert!(c.bold && c.blink && c.reverse);
+        assert_eq!(c.foreground, Color::Red);
+
+        assert_eq!(screen.attributes_at(0, 3), Attributes::default());
+    }
+
+    #[test]
+    fn graphic_attributes_can_be_turned_off_one_by_one() {
+        let screen = screen_after(b"\x1b[1;4;33mA\x1b[22;39mB\x1b[;32mC\x1b[38;5;208mD");
+        assert_eq!(screen.attributes_at(0, 1).foreground, Color::Default);
+        assert!(!screen.attributes_at(0, 1).bold && screen.attributes_at(0, 1).underline);
+
+        // 空的參數視為 0，會先重設屬性
+        assert_eq!(screen.attributes_at(0, 2).foreground, Color::Green);
+        assert!(!screen.attributes_at(0, 2).underline);
+        assert_eq!(screen.attributes_at(0, 3).foreground, Color::Indexed(208));
+    }
+
+    #[test]
+    fn every_256_color_index_is_kept() {
+        let screen = screen_after(b"\x1b[38;5;255;48;5;255mA\x1b[38;5;254mB\x1b[38;5;256mC");
+        assert_eq!(screen.attributes_at(0, 0).foreground, Color::Indexed(255));
+        assert_eq!(screen.attributes_at(0, 0).background, Color::Indexed(255));
+        assert_eq!(screen.attributes_at(0, 1).foreground, Color::Indexed(254));
+        // 超出範圍的顏色不套用
+        assert_eq!(screen.attributes_at(0, 2).foreground, Color::Indexed(254));
+    }
+
+    #[test]
+    fn double_byte_characters_survive_attribute_changes() {
+        // "中" 的前後兩半用不同顏色
+        let screen = screen_after(b"\x1b[31m\xa4\x1b[32m\xa4!");
+        assert_eq!(&screen.row_text(0)[.. 4], "中!");
+        assert!(!screen.is_broken(0, 0) && !screen.is_broken(0, 1));
+    }
+
+    #[test]
+    fn overwritten_halves_become_spaces_without_shifting_the_row() {
+        // 在 "中文" 的 "中" 後半寫入 X
+        let screen = screen_after(b"\xa4\xa4\xa4\xe5\x1b[1;2HX");
+        assert!(screen.is_broken(0, 0));
+        assert_eq!(&screen.row_text(0)[.. 5], " X文");
+        assert_eq!(&screen.row_bytes(0)[.. 4], b" X\xa4\xe5");
+
+        // 把 "文" 的前半蓋掉
+        let screen = screen_after(b"\xa4\xa4\xa4\xe5\x1b[1;3HY");
+        assert!(screen.is_broken(0, 3));
+        assert_eq!(&screen.row_bytes(0)[.. 4], b"\xa4\xa4Y ");
+    }
+
+    #[test]
+    fn halves_split_by_a_cursor_move_are_broken() {
+        let screen = screen_after(b"\xa4\x1b[2;1H\xa4");
+        assert!(screen.is_broken(0, 0) && screen.is_broken(1, 0));
+        assert_eq!(&screen.row_text(0)[.. 1], " ");
+    }
+
+    #[test]
+    fn row_text_maps_characters_to_columns() {
+        let row = screen_after(b"AB\xa4\xa4C").row_text_columns(0);
+        assert_eq!(&row.columns[.. 4], &[0, 1, 2, 4]);
+        assert_eq!(row.column_of(row.text.find('C').unwrap()), Some(4));
+        assert_eq!(RowText { text: "中".to_string(), columns: vec![0] }.column_of(1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "screen size must be at least 1x1")]
+    fn empty_screens_are_rejected() {
+        Screen::with_size(80, 0);
+    }
+
+    #[test]
+    fn text_past_the_last_column_wraps() {
+        let mut screen = Screen::with_size(4, 2);
+        screen.process(b"ABCDEF");
+        assert_eq!(screen.row_text(0), "ABCD");
+        assert_eq!(screen.row_text(1), "EF  ");
+        assert_eq!(screen.get_cursor_position(), (1, 2));
+    }
+
+    #[test]
+    fn line_feed_on_the_last_row_scrolls() {
+        let mut screen = Screen::with_size(4, 3);
+        screen.process(b"A\r\nB\r\nC\r\nD");
+        assert_eq!(screen.snapshot(), "B   \nC   \nD   ");
+        assert_eq!(screen.get_cursor_position(), (2, 1));
+    }
+
+    #[test]
+    fn scroll_tokens_move_the_lines() {
+        let mut screen = Screen::with_size(4, 3);
+        screen.process(b"A\r\nB\r\nC\x1b[2S");
+        assert_eq!(screen.snapshot(), "C   \n    \n    ");
+
+        screen.process(b"\x1b[T");
+        assert_eq!(screen.snapshot(), "    \nC   \n    ");
+    }
+
+    #[test]
+    fn scroll_region_keeps_the_other_rows() {
+        let mut screen = Screen::with_size(4, 4);
+        screen.process(b"\x1b[1;1HH\x1b[4;1HF\x1b[2;3r\x1b[2;1HA\r\nB\r\nC");
+        assert_eq!(screen.snapshot(), "H   \nB   \nC   \nF   ");
+
+        // 在範圍頂端反向換行會往下捲
+        screen.process(b"\x1b[2;1H\x1bM");
+        assert_eq!(screen.snapshot(), "H   \n    \nB   \nF   ");
+
+        // 重設捲動範圍
+        screen.process(b"\x1b[r\x1b[4;1H\n");
+        assert_eq!(screen.snapshot(), "    \nB   \nF   \n    ");
+    }
+
+    #[test]
+    fn scrolled_off_lines_are_kept_in_scrollback() {
+        let mut screen = Screen::with_size(4, 2);
+        screen.process(b"A\r\nB\r\n");
+        assert!(screen.take_scrollback().is_empty());
+
+        screen.set_scrollback_limit(2);
+        screen.process(b"C\r\nD\r\nE\r\n");
+        assert_eq!(screen.take_scrollback(), vec!["C   ", "D   "]);
+        assert!(screen.take_scrollback().is_empty());
+    }
+
+    // 4x3 的畫面填滿字，游標在中間那一行的 'F'
+    fn erased(sequence: &[u8]) -> String {
+        let mut screen = Screen::with_size(4, 3);
+        screen.process(b"ABCD\x1b[2;1HEFGH\x1b[3;1HIJKL\x1b[2;2H");
+        screen.process(sequence);
+        screen.snapshot()
+    }
+
+    #[test]
+    fn erase_line() {
+        assert_eq!(erased(b"\x1b[K"), "ABCD\nE   \nIJKL");
+        assert_eq!(erased(b"\x1b[0K"), "ABCD\nE   \nIJKL");
+        assert_eq!(erased(b"\x1b[1K"), "ABCD\n  GH\nIJKL");
+        assert_eq!(erased(b"\x1b[2K"), "ABCD\n    \nIJKL");
+    }
+
+    #[test]
+    fn erase_display() {
+        assert_eq!(erased(b"\x1b[J"), "ABCD\nE   \n    ");
+        assert_eq!(erased(b"\x1b[0J"), "ABCD\nE   \n    ");
+        assert_eq!(erased(b"\x1b[1J"), "    \n  GH\nIJKL");
+        assert_eq!(erased(b"\x1b[2J"), "    \n    \n    ");
+    }
+
+    #[test]
+    fn erase_with_the_cursor_past_the_last_column() {
+        let mut screen = Screen::with_size(4, 2);
+        screen.process(b"ABCD\x1b[1K");
+        assert_eq!(screen.snapshot(), "    \n    ");
+        screen.process(b"\x1b[1;1HABCD\x1b[K");
+        assert_eq!(screen.snapshot(), "ABCD\n    ");
+    }
+
+    #[test]
+    fn erase_display_and_buffer_clears_the_scrollback() {
+        let mut screen = Screen::with_size(4, 2);
+        screen.set_scrollback_limit(2);
+        screen.process(b"A\r\nB\r\nC\x1b[3J");
+        assert_eq!(screen.snapshot(), "    \n    ");
+        assert!(screen.take_scrollback().is_empty());
+    }
+
+    #[test]
+    fn malformed_input_does_not_break_the_stream() {
+        let screen = screen_after(b"\x1b[?25l\x1b[1<;3HA\x1b[12\x18B\x1b]0;title\x07C\x1b(BD");
+        assert_eq!(&screen.row_text(0)[.. 6], "  ABCD");
+    }
+
+    #[test]
+    fn reset_clears_the_screen_and_attributes() {
+        let screen = screen_after(b"\x1b[31mA\x1b[2;3r\x1bcB");
+        assert_eq!(&screen.row_text(0)[.. 2], "B ");
+        assert_eq!(screen.attributes_at(0, 0), Attributes::default());
+        assert_eq!(screen.get_cursor_position(), (0, 1));
+    }
 }
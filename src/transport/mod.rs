@@ -0,0 +1,25 @@
+mod record;
+mod replay;
+mod session;
+mod ssh;
+mod telnet;
+mod websocket;
+
+use std::time::Duration;
+
+use crate::error::CrawlerError;
+
+pub use self::record::RecordingTransport;
+pub use self::replay::ReplayTransport;
+pub use self::session::{Direction, Session, SessionEvent};
+pub use self::ssh::SshTransport;
+pub use self::telnet::TelnetTransport;
+pub use self::websocket::WebSocketTransport;
+
+// PttConnection 跟伺服器之間傳送 byte 的方式
+pub trait Transport {
+    // 在 timeout 之內讀到資料就回傳，等不到資料則回傳 None
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, CrawlerError>;
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError>;
+}
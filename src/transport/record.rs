@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::error::CrawlerError;
+use super::Transport;
+use super::session::{Direction, SessionEvent, SESSION_HEADER};
+
+// 把另一個 transport 收送的資料一筆一筆寫進 session 檔，之後可以用 ReplayTransport 重播
+pub struct RecordingTransport {
+    inner: Box<dyn Transport>,
+    output: Box<dyn Write>,
+    started: Instant
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Box<dyn Transport>, mut output: Box<dyn Write>) -> Result<RecordingTransport, CrawlerError> {
+        output.write_all(SESSION_HEADER.as_bytes())?;
+        Ok(RecordingTransport {
+            inner,
+            output,
+            started: Instant::now()
+        })
+    }
+
+    pub fn create<P: AsRef<Path>>(inner: Box<dyn Transport>, path: P) -> Result<RecordingTransport, CrawlerError> {
+        RecordingTransport::new(inner, Box::new(BufWriter::new(File::create(path)?)))
+    }
+
+    // 每筆都立刻寫出去，程式中途失敗時也能留下紀錄
+    fn record(&mut self, direction: Direction, data: &[u8]) -> Result<(), CrawlerError> {
+        let event = SessionEvent {
+            elapsed: self.started.elapsed(),
+            direction,
+            data: data.to_vec()
+        };
+        writeln!(self.output, "{}", event)?;
+        self.output.flush()?;
+        Ok(())
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, CrawlerError> {
+        let data = self.inner.read_timeout(timeout)?;
+        if let Some(ref data) = data {
+            self.record(Direction::Inbound, data)?;
+        }
+        Ok(data)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        self.record(Direction::Outbound, bytes)?;
+        self.inner.write(bytes)
+    }
+}
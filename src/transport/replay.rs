@@ -0,0 +1,63 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::CrawlerError;
+use super::Transport;
+use super::session::{Direction, Session, SessionEvent};
+
+// 重播錄下來的 session：依序送出收到的資料，並檢查送出的按鍵是否與紀錄相同
+pub struct ReplayTransport {
+    events: VecDeque<SessionEvent>
+}
+
+impl ReplayTransport {
+    pub fn new(session: Session) -> ReplayTransport {
+        ReplayTransport {
+            events: session.events.into()
+        }
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ReplayTransport, CrawlerError> {
+        Session::load(path).map(ReplayTransport::new)
+    }
+
+    // 還沒被讀走或送出的事件數
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl Transport for ReplayTransport {
+    // 紀錄中下一筆是送出的資料時，代表伺服器正在等待按鍵，所以直接視為 timeout
+    fn read_timeout(&mut self, _: Duration) -> Result<Option<Vec<u8>>, CrawlerError> {
+        match self.events.front() {
+            Some(event) if event.direction == Direction::Inbound => Ok(self.events.pop_front().map(|e| e.data)),
+            _ => Ok(None)
+        }
+    }
+
+    // 送出的資料可以跟紀錄切得不一樣，只要整串 byte 相同即可
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        let mut remaining = bytes;
+        while !remaining.is_empty() {
+            let position = match self.events.iter().position(|e| e.direction == Direction::Outbound) {
+                Some(position) => position,
+                None => return Err(CrawlerError::ReplayMismatch { expected: vec![], actual: bytes.to_vec() })
+            };
+            let event = &mut self.events[position];
+
+            let n = event.data.len().min(remaining.len());
+            if event.data[.. n] != remaining[.. n] {
+                return Err(CrawlerError::ReplayMismatch { expected: event.data.clone(), actual: bytes.to_vec() });
+            }
+            event.data.drain(.. n);
+            remaining = &remaining[n ..];
+
+            if event.data.is_empty() {
+                self.events.remove(position);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::CrawlerError;
+
+pub const SESSION_HEADER: &str = "# ptt-crawler session\n# <秒數> <方向：< 收到、> 送出> <資料 (hex)>\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Inbound,
+    Outbound
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionEvent {
+    pub elapsed: Duration, // 從連線開始經過的時間
+    pub direction: Direction,
+    pub data: Vec<u8>
+}
+
+// 錄下來的連線內容，檔案中每一行是一筆事件，例如 "0.125 < 1b5b324a"
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Session {
+    pub events: Vec<SessionEvent>
+}
+
+impl Session {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Session, CrawlerError> {
+        Session::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Session, CrawlerError> {
+        let mut events = vec![];
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let event = parse_event(line).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("session 第 {} 行格式錯誤：{}", n + 1, line))
+            })?;
+            events.push(event);
+        }
+        Ok(Session {
+            events
+        })
+    }
+}
+
+impl fmt::Display for SessionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Inbound => '<',
+            Direction::Outbound => '>'
+        };
+        write!(f, "{:.3} {} ", self.elapsed.as_secs_f64(), direction)?;
+        for b in &self.data {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", SESSION_HEADER)?;
+        for event in &self.events {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_event(line: &str) -> Option<SessionEvent> {
+    let mut fields = line.split_whitespace();
+    let elapsed = Duration::try_from_secs_f64(fields.next()?.parse().ok()?).ok()?;
+    let direction = match fields.next()? {
+        "<" => Direction::Inbound,
+        ">" => Direction::Outbound,
+        _ => return None
+    };
+    let hex = fields.next().unwrap_or("");
+    if fields.next().is_some() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    let data = (0 .. hex.len()).step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i .. i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(SessionEvent {
+        elapsed,
+        direction,
+        data
+    })
+}
@@ -0,0 +1,109 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use russh::{self, ChannelMsg};
+use russh::client::{self, Handle, Msg};
+use russh::keys::{HashAlg, PublicKeyOrCertificate};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::config::ConnectionConfig;
+use crate::error::CrawlerError;
+use super::Transport;
+
+const TERMINAL_TYPE: &str = "vt100";
+
+struct SshClient {
+    trusted_keys: Vec<String>,
+    rejected_key: Arc<Mutex<Option<String>>> // 被拒絕的金鑰指紋，用來產生錯誤訊息
+}
+
+impl client::Handler for SshClient {
+    type Error = russh::Error;
+
+    // 只接受設定中的金鑰，避免帳號密碼被中間人攔截
+    async fn check_server_key(&mut self, key: &PublicKeyOrCertificate) -> Result<bool, Self::Error> {
+        let key_data = match *key {
+            PublicKeyOrCertificate::PublicKey { ref key, .. } => key.key_data(),
+            PublicKeyOrCertificate::Certificate(ref certificate) => certificate.public_key()
+        };
+        let fingerprint = key_data.fingerprint(HashAlg::Sha256).to_string();
+        if self.trusted_keys.contains(&fingerprint) {
+            return Ok(true);
+        }
+        *self.rejected_key.lock().unwrap() = Some(fingerprint);
+        Ok(false)
+    }
+}
+
+// 用 SSH 連到 PTT (例如 bbsu@ptt.cc)，帳號密碼會經過加密
+pub struct SshTransport {
+    runtime: Runtime,
+    // 保留 session 以免連線被關閉
+    _session: Handle<SshClient>,
+    channel: russh::Channel<Msg>
+}
+
+impl SshTransport {
+    pub fn connect(config: &ConnectionConfig, user: &str) -> Result<SshTransport, CrawlerError> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()?;
+
+        let addr = (config.get_host().to_string(), config.get_port());
+        let (width, height) = config.get_terminal_size();
+        let rejected_key = Arc::new(Mutex::new(None));
+        let client = SshClient {
+            trusted_keys: config.get_ssh_host_keys().to_vec(),
+            rejected_key: rejected_key.clone()
+        };
+        let (session, channel) = runtime.block_on(async {
+            let mut session = client::connect(Arc::new(client::Config::default()), addr, client).await
+                .map_err(|e| match (e, rejected_key.lock().unwrap().take()) {
+                    (_, Some(fingerprint)) => CrawlerError::UnknownHostKey(fingerprint),
+                    (russh::Error::IO(e), None) => CrawlerError::ConnectFailed(e),
+                    (e, None) => CrawlerError::from(e)
+                })?;
+
+            // PTT 不需要 SSH 密碼，真正的帳號密碼是在登入畫面輸入
+            if !session.authenticate_none(user).await?.success()
+                && !session.authenticate_password(user, "").await?.success() {
+                return Err(CrawlerError::from(russh::Error::NotAuthenticated));
+            }
+
+            let channel = session.channel_open_session().await?;
+            channel.request_pty(false, TERMINAL_TYPE, width as u32, height as u32, 0, 0, &[]).await?;
+            channel.request_shell(false).await?;
+            Ok((session, channel))
+        })?;
+
+        Ok(SshTransport {
+            runtime,
+            _session: session,
+            channel
+        })
+    }
+}
+
+impl Transport for SshTransport {
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, CrawlerError> {
+        let channel = &mut self.channel;
+        self.runtime.block_on(async {
+            loop {
+                match tokio::time::timeout(timeout, channel.wait()).await {
+                    Err(_) => return Ok(None),
+                    Ok(Some(ChannelMsg::Data { data })) => return Ok(Some(data.to_vec())),
+                    Ok(Some(ChannelMsg::Eof)) | Ok(Some(ChannelMsg::Close)) | Ok(None) => {
+                        return Err(CrawlerError::from(russh::Error::Disconnect));
+                    },
+                    Ok(Some(_)) => {}
+                }
+            }
+        })
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        self.runtime.block_on(self.channel.data(bytes))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use std::io;
+use std::time::Duration;
+
+use telnet::{Telnet, TelnetEvent, NegotiationAction, TelnetOption};
+
+use crate::config::ConnectionConfig;
+use crate::error::CrawlerError;
+use super::Transport;
+
+pub struct TelnetTransport {
+    tel_conn: Telnet,
+    terminal_size: (usize, usize)
+}
+
+impl TelnetTransport {
+    pub fn connect(config: &ConnectionConfig) -> Result<TelnetTransport, CrawlerError> {
+        let tel_conn = Telnet::connect((config.get_host(), config.get_port()), config.get_buffer_size())
+            .map_err(CrawlerError::ConnectFailed)?;
+        Ok(TelnetTransport {
+            tel_conn,
+            terminal_size: config.get_terminal_size()
+        })
+    }
+
+    fn send_window_size(&mut self) {
+        let (width, height) = self.terminal_size;
+        let data = [(width >> 8) as u8, width as u8, (height >> 8) as u8, height as u8];
+        self.tel_conn.negotiate(NegotiationAction::Will, TelnetOption::NAWS);
+        self.tel_conn.subnegotiate(TelnetOption::NAWS, &data);
+    }
+}
+
+impl Transport for TelnetTransport {
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, CrawlerError> {
+        loop {
+            match self.tel_conn.read_timeout(timeout)? {
+                TelnetEvent::Data(data) => return Ok(Some(data.to_vec())),
+                TelnetEvent::TimedOut => return Ok(None),
+                TelnetEvent::Negotiation(NegotiationAction::Do, TelnetOption::NAWS) => {
+                    self.send_window_size();
+                },
+                // 連線被關閉時 telnet 會一直回傳這個錯誤，不處理的話會卡在這裡
+                TelnetEvent::Error(message) => {
+                    return Err(CrawlerError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, message)));
+                },
+                _ => {} // 其他的 telnet 協商先忽略
+            }
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        self.tel_conn.write(bytes)?;
+        Ok(())
+    }
+}
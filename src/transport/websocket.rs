@@ -0,0 +1,66 @@
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::time::Duration;
+
+use tungstenite::{self, Message, WebSocket};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::HeaderValue;
+use tungstenite::stream::MaybeTlsStream;
+
+use crate::error::CrawlerError;
+use super::Transport;
+
+// PTT 的 WebSocket 伺服器只接受來自網頁版終端機的連線
+const PTT_WEB_ORIGIN: &str = "https://term.ptt.cc";
+
+pub struct WebSocketTransport {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>
+}
+
+impl WebSocketTransport {
+    pub fn connect(url: &str) -> Result<WebSocketTransport, CrawlerError> {
+        let mut request = url.into_client_request()?;
+        request.headers_mut().insert("Origin", HeaderValue::from_static(PTT_WEB_ORIGIN));
+
+        let (socket, _) = tungstenite::connect(request).map_err(|e| match e {
+            tungstenite::Error::Io(e) => CrawlerError::ConnectFailed(e),
+            e => CrawlerError::from(e)
+        })?;
+        Ok(WebSocketTransport {
+            socket
+        })
+    }
+
+    fn set_read_timeout(&mut self, timeout: Duration) -> Result<(), CrawlerError> {
+        let stream = match *self.socket.get_mut() {
+            MaybeTlsStream::Plain(ref mut s) => s,
+            MaybeTlsStream::Rustls(ref mut s) => s.get_mut(),
+            _ => return Ok(())
+        };
+        stream.set_read_timeout(Some(timeout))?;
+        Ok(())
+    }
+}
+
+impl Transport for WebSocketTransport {
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, CrawlerError> {
+        self.set_read_timeout(timeout)?;
+        loop {
+            match self.socket.read() {
+                Ok(Message::Binary(data)) => return Ok(Some(data)),
+                Ok(Message::Text(text)) => return Ok(Some(text.into_bytes())),
+                Ok(_) => {}, // Ping/Pong 由 tungstenite 處理
+                Err(tungstenite::Error::Io(ref e))
+                    if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                    return Ok(None);
+                },
+                Err(e) => return Err(CrawlerError::from(e))
+            }
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        self.socket.send(Message::Binary(bytes.to_vec()))?;
+        Ok(())
+    }
+}
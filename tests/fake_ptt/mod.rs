@@ -0,0 +1,676 @@
+// 整合測試用的假 PTT：在本機開一個 telnet port，畫出 Big5 + ANSI 的畫面並回應按鍵
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use ptt_crawler::ConnectionConfig;
+use ptt_crawler::screen::{Big5Uao, ScreenEncoding};
+
+const HEIGHT: usize = 24;
+const LIST_ROWS: usize = 20;     // 文章列表第 4 ~ 23 行
+const ARTICLE_ROWS: usize = 23;  // 文章內容最後一行是狀態列
+const LOADING_TIME: Duration = Duration::from_millis(400);
+
+// 登入後、進到主功能表前可能出現的畫面
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoginHurdle {
+    Loading,         // 登入中，請稍候... (不用按鍵，過一陣子自己換掉)
+    DuplicateLogin,  // 您想刪除其他重複登入的連線嗎？
+    FailedAttempts,  // 您要刪除以上錯誤嘗試的記錄嗎?
+    TooFrequent,     // 請勿頻繁登入以免造成系統過度負荷
+    MailboxFull,     // 您保存信件數目超出上限
+    Announcement     // 週年慶之類的公告
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeArticle {
+    pub author: String,
+    pub title: String,
+    pub date: String,       // " 8/03"
+    pub push: String,       // 列表上的推文數，例如 "爆"、"12"、"X1"
+    pub filename: String,   // "M.1700000001.A.001"，文章代碼 (AID) 由它算出來
+    pub body: Vec<String>,
+    pub pushes: Vec<String> // 推文行，例如 "推 someone: 好文 08/03 12:00"
+}
+
+impl FakeArticle {
+    pub fn new(author: &str, title: &str) -> FakeArticle {
+        FakeArticle {
+            author: author.to_string(),
+            title: title.to_string(),
+            date: " 8/03".to_string(),
+            push: String::new(),
+            filename: "M.1700000000.A.000".to_string(),
+            body: vec!["內文".to_string()],
+            pushes: vec![]
+        }
+    }
+
+    pub fn push(mut self, push: &str) -> FakeArticle {
+        self.push = push.to_string();
+        self
+    }
+
+    pub fn filename(mut self, filename: &str) -> FakeArticle {
+        self.filename = filename.to_string();
+        self
+    }
+
+    // 例如 "M.1700000001.A.001" => "1bK_4101"
+    pub fn aid(&self) -> String {
+        const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz-_";
+        let parts: Vec<&str> = self.filename.split('.').collect();
+        let kind: u64 = if parts[0] == "G" { 1 } else { 0 };
+        let value = kind << 44 | parts[1].parse::<u64>().unwrap() << 12 | u64::from_str_radix(parts[3], 16).unwrap();
+        (0 .. 8).rev().map(|i| DIGITS[(value >> (i * 6) & 63) as usize] as char).collect()
+    }
+
+    pub fn url(&self, board: &str) -> String {
+        format!("https://www.ptt.cc/bbs/{}/{}.html", board, self.filename)
+    }
+
+    pub fn body(mut self, lines: Vec<String>) -> FakeArticle {
+        self.body = lines;
+        self
+    }
+
+    pub fn pushes(mut self, lines: Vec<String>) -> FakeArticle {
+        self.pushes = lines;
+        self
+    }
+
+    // 文章瀏覽畫面中的每一行
+    fn lines(&self, board: &str) -> Vec<String> {
+        let mut lines = vec![
+            format!(" 作者  {} ()                                          看板  {}", self.author, board),
+            format!(" 標題  {}", self.title),
+            " 時間  Sun Aug  3 12:00:00 2025".to_string(),
+            "─".repeat(39)
+        ];
+        lines.extend(self.body.iter().cloned());
+        lines.push("--".to_string());
+        lines.push("※ 發信站: 批踢踢實業坊(ptt.cc), 來自: 127.0.0.1 (臺灣)".to_string());
+        lines.push(format!("※ 文章網址: {}", self.url(board)));
+        lines.extend(self.pushes.iter().cloned());
+        lines
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeBoard {
+    pub name: String,
+    pub adult: bool, // 進板前會問是否滿十八歲
+    pub articles: Vec<FakeArticle>,
+    pub pinned: Vec<FakeArticle>
+}
+
+impl FakeBoard {
+    pub fn new(name: &str) -> FakeBoard {
+        FakeBoard {
+            name: name.to_string(),
+            adult: false,
+            articles: vec![],
+            pinned: vec![]
+        }
+    }
+
+    // 第 n 篇的標題是 "[問卦] 測試文章 n"
+    pub fn with_articles(mut self, count: usize) -> FakeBoard {
+        for n in 1 ..= count {
+            let article = FakeArticle::new(&format!("user{}", n), &format!("[問卦] 測試文章 {}", n))
+                .filename(&format!("M.{}.A.{:03X}", 1700000000 + n, n));
+            self.articles.push(article);
+        }
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct FakePtt {
+    account: String,
+    password: String,
+    hurdles: Vec<LoginHurdle>,
+    boards: Vec<FakeBoard>,
+    answers: Arc<Mutex<Vec<(LoginHurdle, String)>>> // 所有連線對 Y/N 問題的回答
+}
+
+impl FakePtt {
+    // 帳號 account、密碼 password，有一個 45 篇文章加 2 篇置底的八卦板 (需滿十八歲)
+    pub fn new() -> FakePtt {
+        let mut gossiping = FakeBoard::new("Gossiping").with_articles(45);
+        gossiping.adult = true;
+        gossiping.articles[0].push = "爆".to_string();
+        gossiping.articles[1].push = "12".to_string();
+        gossiping.articles[2].push = "X1".to_string();
+        gossiping.pinned.push(FakeArticle::new("SYSOP", "[公告] 板規"));
+        gossiping.pinned.push(FakeArticle::new("SYSOP", "[公告] 置底閒聊"));
+        FakePtt {
+            account: "account".to_string(),
+            password: "password".to_string(),
+            hurdles: vec![],
+            boards: vec![gossiping, FakeBoard::new("Test").with_articles(3)],
+            answers: Arc::new(Mutex::new(vec![]))
+        }
+    }
+
+    pub fn hurdle(mut self, hurdle: LoginHurdle) -> FakePtt {
+        self.hurdles.push(hurdle);
+        self
+    }
+
+    pub fn board(mut self, board: FakeBoard) -> FakePtt {
+        self.boards.retain(|b| b.name != board.name);
+        self.boards.push(board);
+        self
+    }
+
+    pub fn start(self) -> FakePttServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let answers = self.answers.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let ptt = self.clone();
+                thread::spawn(move || Session::new(ptt, stream.unwrap()).run());
+            }
+        });
+        FakePttServer {
+            port,
+            answers
+        }
+    }
+}
+
+pub struct FakePttServer {
+    pub port: u16,
+    answers: Arc<Mutex<Vec<(LoginHurdle, String)>>>
+}
+
+impl FakePttServer {
+    // 測試用的設定：連到這個假伺服器，並縮短等待時間
+    pub fn config(&self) -> ConnectionConfig {
+        ConnectionConfig::new()
+            .host("127.0.0.1")
+            .port(self.port)
+            .wait_timeout(Duration::from_millis(150))
+            .operation_deadline(Duration::from_secs(10))
+    }
+
+    pub fn answers(&self) -> Vec<(LoginHurdle, String)> {
+        self.answers.lock().unwrap().clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Key {
+    Byte(u8),
+    Enter,
+    Up,
+    Down,
+    Right,
+    Left,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Unknown
+}
+
+enum State {
+    Account(String),
+    Password(String, String),
+    Hurdle(LoginHurdle, String),
+    Welcome,
+    MainMenu,
+    BoardPrompt(String),
+    AdultPrompt(usize, String),
+    BoardList(ListView),
+    JumpPrompt(ListView, String),
+    SearchPrompt(ListView, u8, Vec<u8>), // 按下的搜尋鍵 (/ a Z #) 與輸入的 Big5
+    AidNotFound(ListView),
+    ArticleInfo(ListView),               // 按 Q 看到的文章代碼與網址
+    Article(ListView, usize)             // 畫面第一行是文章的第幾行
+}
+
+// 目前看到的文章列表，filter 是搜尋結果 (文章的 index)，cursor 是列表中的第幾項
+#[derive(Clone)]
+struct ListView {
+    board: usize,
+    cursor: usize,
+    filter: Option<Vec<usize>>
+}
+
+impl ListView {
+    fn new(board: usize, cursor: usize) -> ListView {
+        ListView {
+            board,
+            cursor,
+            filter: None
+        }
+    }
+
+    fn cursor(mut self, cursor: usize) -> ListView {
+        self.cursor = cursor;
+        self
+    }
+}
+
+struct Session {
+    ptt: FakePtt,
+    stream: TcpStream,
+    state: State,
+    hurdles: Vec<LoginHurdle>,
+    input: Vec<u8>
+}
+
+impl Session {
+    fn new(ptt: FakePtt, stream: TcpStream) -> Session {
+        Session {
+            ptt,
+            stream,
+            state: State::Account(String::new()),
+            hurdles: vec![],
+            input: vec![]
+        }
+    }
+
+    fn run(mut self) {
+        self.send_screen(&[(21, "請輸入代號，或以 guest 參觀，或以 new 註冊: ".to_string())]);
+        let mut buffer = [0u8; 1024];
+        loop {
+            let n = match self.stream.read(&mut buffer) {
+                Ok(0) | Err(_) => return,
+                Ok(n) => n
+            };
+            self.input.extend_from_slice(&buffer[.. n]);
+            while let Some(key) = self.next_key() {
+                self.handle(key);
+            }
+        }
+    }
+
+    // 從輸入中切出一個按鍵，ESC 序列不完整時等下一筆資料
+    fn next_key(&mut self) -> Option<Key> {
+        let (key, len) = match *self.input.first()? {
+            27 => {
+                let end = self.input.iter().skip(1).position(|b| b.is_ascii_alphabetic() || *b == b'~')? + 2;
+                let key = match &self.input[1 .. end] {
+                    b"[A" => Key::Up,
+                    b"[B" => Key::Down,
+                    b"[C" => Key::Right,
+                    b"[D" => Key::Left,
+                    b"[1~" => Key::Home,
+                    b"[4~" => Key::End,
+                    b"[5~" => Key::PageUp,
+                    b"[6~" => Key::PageDown,
+                    _ => Key::Unknown
+                };
+                (key, end)
+            },
+            b'\r' => (Key::Enter, 1),
+            b'\n' | 0 => (Key::Unknown, 1),
+            b => (Key::Byte(b), 1)
+        };
+        self.input.drain(.. len);
+        if key == Key::Unknown {
+            return self.next_key();
+        }
+        Some(key)
+    }
+
+    fn handle(&mut self, key: Key) {
+        let state = std::mem::replace(&mut self.state, State::MainMenu);
+        self.state = match state {
+            State::Account(account) => match key {
+                Key::Enter => {
+                    self.send(&[(22, "請輸入您的密碼: ".to_string())]);
+                    State::Password(account, String::new())
+                },
+                Key::Byte(b) => {
+                    self.write(&[b]);
+                    State::Account(account + &(b as char).to_string())
+                },
+                _ => State::Account(account)
+            },
+            State::Password(account, password) => match key {
+                Key::Enter if account == self.ptt.account && password == self.ptt.password => {
+                    self.hurdles = self.ptt.hurdles.clone();
+                    self.next_hurdle()
+                },
+                Key::Enter => {
+                    self.send(&[
+                        (21, "密碼不對或無此帳號。請檢查大小寫及有無輸入錯誤。".to_string()),
+                        (22, "請重新輸入代號，或以 guest 參觀，或以 new 註冊: ".to_string())
+                    ]);
+                    State::Account(String::new())
+                },
+                Key::Byte(b) => State::Password(account, password + &(b as char).to_string()),
+                _ => State::Password(account, password)
+            },
+            State::Hurdle(hurdle, answer) => match (hurdle, key) {
+                (LoginHurdle::DuplicateLogin, Key::Enter) | (LoginHurdle::FailedAttempts, Key::Enter) => {
+                    self.ptt.answers.lock().unwrap().push((hurdle, answer));
+                    self.next_hurdle()
+                },
+                (LoginHurdle::DuplicateLogin, Key::Byte(b)) | (LoginHurdle::FailedAttempts, Key::Byte(b)) => {
+                    self.write(&[b]);
+                    State::Hurdle(hurdle, answer + &(b as char).to_string())
+                },
+                (LoginHurdle::DuplicateLogin, _) | (LoginHurdle::FailedAttempts, _) => State::Hurdle(hurdle, answer),
+                _ => self.next_hurdle()
+            },
+            State::Welcome => self.main_menu(),
+            State::MainMenu => match key {
+                Key::Byte(b's') | Key::Byte(b'S') => {
+                    self.send(&[(1, "請輸入看板名稱(按空白鍵自動搜尋): ".to_string())]);
+                    State::BoardPrompt(String::new())
+                },
+                _ => State::MainMenu
+            },
+            State::BoardPrompt(name) => match key {
+                Key::Enter => {
+                    match self.ptt.boards.iter().position(|b| b.name.eq_ignore_ascii_case(&name)) {
+                        Some(board) if self.ptt.boards[board].adult => {
+                            self.send_screen(&[(10, "本看板只適合十八歲以上讀者閱讀，您滿十八歲了嗎？ [y/N]: ".to_string())]);
+                            State::AdultPrompt(board, String::new())
+                        },
+                        Some(board) => self.board_list(ListView::new(board, usize::MAX)),
+                        None => self.main_menu()
+                    }
+                },
+                Key::Byte(b) => {
+                    self.write(&[b]);
+                    State::BoardPrompt(name + &(b as char).to_string())
+                },
+                _ => State::BoardPrompt(name)
+            },
+            State::AdultPrompt(board, answer) => match key {
+                Key::Enter if answer.eq_ignore_ascii_case("y") => self.board_list(ListView::new(board, usize::MAX)),
+                Key::Enter => self.main_menu(),
+                Key::Byte(b) => {
+                    self.write(&[b]);
+                    State::AdultPrompt(board, answer + &(b as char).to_string())
+                },
+                _ => State::AdultPrompt(board, answer)
+            },
+            State::BoardList(view) => {
+                let cursor = view.cursor;
+                let last = self.entries(&view).len() - 1;
+                match key {
+                    Key::Up => self.board_list(view.cursor(cursor.saturating_sub(1))),
+                    Key::Down => self.board_list(view.cursor((cursor + 1).min(last))),
+                    Key::PageUp => self.board_list(view.cursor(cursor.saturating_sub(LIST_ROWS))),
+                    Key::PageDown => self.board_list(view.cursor((cursor + LIST_ROWS).min(last))),
+                    Key::Home => self.board_list(view.cursor(0)),
+                    Key::End | Key::Byte(b'$') => self.board_list(view.cursor(last)),
+                    Key::Right | Key::Enter | Key::Byte(b'r') => self.article(view, 0),
+                    Key::Byte(b'Q') => self.article_info(view),
+                    Key::Left if view.filter.is_some() => {
+                        let entry = self.entries(&view)[cursor];
+                        self.board_list(ListView::new(view.board, entry))
+                    },
+                    Key::Left => self.main_menu(),
+                    Key::Byte(b) if b.is_ascii_digit() => {
+                        let input = (b as char).to_string();
+                        self.send(&[(HEIGHT - 1, format!("跳至第幾項: {}", input))]);
+                        State::JumpPrompt(view, input)
+                    },
+                    Key::Byte(b) if b"/aZ#".contains(&b) => {
+                        let prompt = match b {
+                            b'/' => "[搜尋]關鍵字: ",
+                            b'a' => "[搜尋]作者: ",
+                            b'Z' => "[搜尋]推文數(<0 為噓文數): ",
+                            _ => "搜尋文章代碼(AID): #"
+                        };
+                        self.send(&[(HEIGHT - 1, prompt.to_string())]);
+                        State::SearchPrompt(view, b, vec![])
+                    },
+                    _ => State::BoardList(view)
+                }
+            },
+            State::JumpPrompt(view, input) => match key {
+                Key::Enter => {
+                    let entries = self.entries(&view);
+                    let articles = self.ptt.boards[view.board].articles.len();
+                    let n = input.parse::<usize>().unwrap_or(0);
+                    match entries.iter().position(|&e| e + 1 == n && e < articles) {
+                        Some(cursor) => self.board_list(view.cursor(cursor)),
+                        None => self.board_list(view)
+                    }
+                },
+                Key::Byte(b) if b.is_ascii_digit() => {
+                    self.write(&[b]);
+                    State::JumpPrompt(view, input + &(b as char).to_string())
+                },
+                _ => self.board_list(view)
+            },
+            State::SearchPrompt(view, kind, input) => match key {
+                Key::Enter => self.search(view, kind, &input),
+                Key::Byte(b) => {
+                    self.write(&[b]);
+                    let mut input = input;
+                    input.push(b);
+                    State::SearchPrompt(view, kind, input)
+                },
+                _ => State::SearchPrompt(view, kind, input)
+            },
+            State::AidNotFound(view) | State::ArticleInfo(view) => self.board_list(view),
+            State::Article(view, top) => {
+                let total = self.article_lines(&view).len();
+                match key {
+                    Key::PageDown | Key::Byte(b' ') | Key::Right if top + ARTICLE_ROWS < total => {
+                        self.article(view, top + ARTICLE_ROWS)
+                    },
+                    Key::PageUp => self.article(view, top.saturating_sub(ARTICLE_ROWS)),
+                    Key::Left | Key::Byte(b'q') => self.board_list(view),
+                    _ => State::Article(view, top)
+                }
+            }
+        };
+    }
+
+    fn next_hurdle(&mut self) -> State {
+        if self.hurdles.is_empty() {
+            self.send_screen(&[
+                (8, "歡迎您再度拜訪，上次您是從 127.0.0.1 連往本站。".to_string()),
+                (HEIGHT - 1, "請按任意鍵繼續".to_string())
+            ]);
+            return State::Welcome;
+        }
+
+        let hurdle = self.hurdles.remove(0);
+        if hurdle == LoginHurdle::Loading {
+            self.send_screen(&[(HEIGHT - 1, "登入中，請稍候...".to_string())]);
+            thread::sleep(LOADING_TIME);
+            return self.next_hurdle();
+        }
+
+        let rows = match hurdle {
+            LoginHurdle::Loading => unreachable!(),
+            LoginHurdle::DuplicateLogin => vec![
+                (10, "注意: 您有其它連線已登入此帳號。".to_string()),
+                (11, "您想刪除其他重複登入的連線嗎？[Y/n] ".to_string())
+            ],
+            LoginHurdle::FailedAttempts => vec![
+                (10, "您有 3 次輸入密碼錯誤的記錄".to_string()),
+                (11, "您要刪除以上錯誤嘗試的記錄嗎? [m/N] ".to_string())
+            ],
+            LoginHurdle::TooFrequent => vec![
+                (10, "請勿頻繁登入以免造成系統過度負荷".to_string()),
+                (HEIGHT - 1, "請按任意鍵繼續".to_string())
+            ],
+            LoginHurdle::MailboxFull => vec![
+                (10, "您保存信件數目 120 超出上限 100, 請整理".to_string()),
+                (HEIGHT - 1, "請按任意鍵繼續".to_string())
+            ],
+            LoginHurdle::Announcement => vec![
+                (5, "批踢踢 26 週年".to_string()),
+                (7, "感謝大家一路以來的支持".to_string()),
+                (HEIGHT - 1, "                        ● 請按任意鍵繼續 ●".to_string())
+            ]
+        };
+        self.send_screen(&rows);
+        State::Hurdle(hurdle, String::new())
+    }
+
+    fn main_menu(&mut self) -> State {
+        self.send_screen(&[
+            (0, "【主功能表】                    批踢踢實業坊".to_string()),
+            (11, "                   (F)avorite     【 我 的 最愛 】".to_string()),
+            (12, "                   (S)earch       【 搜尋看板 】".to_string())
+        ]);
+        State::MainMenu
+    }
+
+    // 列表中每一項對應的文章：一般文章是 0 ~ n-1，置底文章接在後面
+    fn entries(&self, view: &ListView) -> Vec<usize> {
+        let board = &self.ptt.boards[view.board];
+        match view.filter {
+            Some(ref filter) => filter.clone(),
+            None => (0 .. board.articles.len() + board.pinned.len()).collect()
+        }
+    }
+
+    fn entry(&self, board: usize, entry: usize) -> &FakeArticle {
+        let b = &self.ptt.boards[board];
+        b.articles.get(entry).unwrap_or_else(|| &b.pinned[entry - b.articles.len()])
+    }
+
+    // 沒有符合的文章時停在原本的列表；# 會直接跳到那一篇
+    fn search(&mut self, view: ListView, kind: u8, input: &[u8]) -> State {
+        let query = Big5Uao::new().decode(input);
+        let board = &self.ptt.boards[view.board];
+        let matches: Vec<usize> = (0 .. board.articles.len()).filter(|&i| {
+            let article = &board.articles[i];
+            match kind {
+                b'/' => article.title.to_lowercase().contains(&query.to_lowercase()),
+                b'a' => article.author.eq_ignore_ascii_case(&query),
+                b'Z' => {
+                    let min: i32 = query.parse().unwrap_or(0);
+                    let score = push_score(&article.push);
+                    if min >= 0 { score >= min } else { score <= min }
+                },
+                _ => article.aid() == query.trim_start_matches('#')
+            }
+        }).collect();
+
+        match (kind, matches.first()) {
+            (b'#', Some(&entry)) => self.board_list(ListView::new(view.board, entry)),
+            (b'#', None) => {
+                self.send_screen(&[
+                    (10, "找不到這個文章代碼(AID)，可能是文章已消失，或是你找錯看板了喔！".to_string()),
+                    (HEIGHT - 1, "請按任意鍵繼續".to_string())
+                ]);
+                State::AidNotFound(view)
+            },
+            (_, None) => self.board_list(view),
+            (_, Some(_)) => {
+                let cursor = matches.len() - 1;
+                self.board_list(ListView { board: view.board, cursor, filter: Some(matches) })
+            }
+        }
+    }
+
+    // cursor 超出範圍時停在最後一篇一般文章
+    fn board_list(&mut self, view: ListView) -> State {
+        let entries = self.entries(&view);
+        let articles = self.ptt.boards[view.board].articles.len();
+        let cursor = if view.cursor < entries.len() {
+            view.cursor
+        } else {
+            entries.iter().rposition(|&e| e < articles).unwrap_or(0)
+        };
+        let view = view.cursor(cursor);
+        let b = &self.ptt.boards[view.board];
+
+        let mut rows = vec![
+            (0, format!("【板主:sysop】   {}板   看板《{}》", b.name, b.name)),
+            (1, "[←]離開 [→]閱讀 [Ctrl-P]發表文章 [d]刪除 [z]精華區 [i]看板資訊/設定".to_string()),
+            (2, "   編號    日 期 作  者       文  章  標  題".to_string())
+        ];
+        let first = cursor / LIST_ROWS * LIST_ROWS;
+        for (i, &entry) in entries.iter().enumerate().skip(first).take(LIST_ROWS) {
+            let index = if entry < articles { format!("{:>6}", entry + 1) } else { "    ★".to_string() };
+            let article = self.entry(view.board, entry);
+            let mark = if i == cursor { ">" } else { " " };
+            let row = format!("{}{} {}{:>5} {:<13}□ {}", mark, index, pad_left(&article.push, 2), article.date,
+                article.author, article.title);
+            rows.push((3 + i - first, row));
+        }
+        rows.push((HEIGHT - 1, " 文章選讀  (y)回應(X)推文(^X)轉錄 (=[]<>)相關主題".to_string()));
+        self.send_screen(&rows);
+        State::BoardList(view)
+    }
+
+    // 蓋在列表上的方框，最後一行換成按任意鍵繼續
+    fn article_info(&mut self, view: ListView) -> State {
+        let board = &self.ptt.boards[view.board];
+        let article = self.entry(view.board, self.entries(&view)[view.cursor]);
+        let rows = vec![
+            (5, format!("┌{}┐", "─".repeat(38))),
+            (6, format!("│ 文章代碼(AID): #{} ({}) [ptt.cc] {}", article.aid(), board.name, article.title)),
+            (7, format!("│ 文章網址: {}", article.url(&board.name))),
+            (8, "│ 這一篇文章值 0 Ptt幣".to_string()),
+            (9, format!("└{}┘", "─".repeat(38))),
+            (HEIGHT - 1, "                        ● 請按任意鍵繼續 ●".to_string())
+        ];
+        self.send(&rows);
+        State::ArticleInfo(view)
+    }
+
+    fn article_lines(&self, view: &ListView) -> Vec<String> {
+        let entry = self.entries(view)[view.cursor];
+        self.entry(view.board, entry).lines(&self.ptt.boards[view.board].name)
+    }
+
+    fn article(&mut self, view: ListView, top: usize) -> State {
+        let lines = self.article_lines(&view);
+        let total = lines.len();
+        let last = (top + ARTICLE_ROWS).min(total);
+        let mut rows: Vec<(usize, String)> = lines[top .. last].iter().cloned().enumerate().collect();
+        rows.push((HEIGHT - 1, format!("  瀏覽 第 {}/{} 頁 ({:>3}%)  目前顯示: 第 {:02}~{:02} 行  (←)離開 ",
+            top / ARTICLE_ROWS + 1, total.div_ceil(ARTICLE_ROWS), last * 100 / total, top + 1, last)));
+        self.send_screen(&rows);
+        State::Article(view, top)
+    }
+
+    // 清除畫面後畫出指定的行
+    fn send_screen(&mut self, rows: &[(usize, String)]) {
+        self.write(b"\x1b[H\x1b[2J");
+        self.send(rows);
+    }
+
+    fn send(&mut self, rows: &[(usize, String)]) {
+        for &(row, ref text) in rows {
+            let mut bytes = format!("\x1b[{};1H", row + 1).into_bytes();
+            bytes.extend(Big5Uao::new().encode(text).unwrap());
+            bytes.extend_from_slice(b"\x1b[K");
+            self.write(&bytes);
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let _ = self.stream.write_all(bytes);
+    }
+}
+
+// 以 Big5 的寬度 (中文字佔兩格) 向右對齊
+fn pad_left(text: &str, width: usize) -> String {
+    let len: usize = text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum();
+    format!("{}{}", " ".repeat(width.saturating_sub(len)), text)
+}
+
+// 跟列表上顯示的一樣換算推文數，X1 當作 -10
+fn push_score(push: &str) -> i32 {
+    match push {
+        "" => 0,
+        "爆" => 100,
+        "XX" => -100,
+        _ if push.starts_with('X') => -10 * push[1 ..].parse::<i32>().unwrap_or(0),
+        _ => push.parse().unwrap_or(0)
+    }
+}
@@ -0,0 +1,264 @@
+extern crate ptt_crawler;
+
+mod fake_ptt;
+
+use std::time::{Duration, Instant};
+
+use ptt_crawler::{all, any, expect, ArticleId, ArticleListEntry, CrawlerError, LoginEvent, PttConnection, PushCount};
+
+use fake_ptt::{FakeArticle, FakeBoard, FakePtt, LoginHurdle};
+
+fn login(ptt: FakePtt) -> PttConnection {
+    let server = ptt.start();
+    let mut connection = PttConnection::connect(server.config()).unwrap();
+    connection.login("account", "password").unwrap();
+    connection
+}
+
+#[test]
+fn logs_in_and_lists_the_board() {
+    let mut connection = login(FakePtt::new());
+    connection.enter_board("Gossiping").unwrap();
+
+    // 一進看板時游標停在最後一篇，畫面上是 41 ~ 45 篇加上置底文章
+    let entries = connection.article_list();
+    assert_eq!(entries.len(), 7);
+    assert_eq!(entries[0].index, Some(41));
+    assert_eq!(entries[4].title, "[問卦] 測試文章 45");
+    assert_eq!(entries[4].category, Some("問卦".to_string()));
+    assert_eq!(entries[5].index, None);
+    assert_eq!(entries[6].title, "[公告] 置底閒聊");
+}
+
+#[test]
+fn collects_every_page_of_the_board() {
+    let mut connection = login(FakePtt::new());
+    connection.enter_board("gossiping").unwrap();
+
+    let entries = connection.all_article_list().unwrap();
+    assert_eq!(entries.len(), 47);
+    assert_eq!(entries[0].push_count, Some(PushCount::Explosion));
+    assert_eq!(entries[1].push_count, Some(PushCount::Count(12)));
+    assert_eq!(entries[2].push_count, Some(PushCount::Boo(1)));
+    assert_eq!(entries[44].index, Some(45));
+    assert_eq!(entries[45].author, "SYSOP");
+}
+
+#[test]
+fn reads_a_multi_page_article() {
+    let body: Vec<String> = (1 ..= 40).map(|n| format!("第 {} 行", n)).collect();
+    let article = FakeArticle::new("writer", "[心得] 很長的文章")
+        .body(body)
+        .pushes(vec![
+            "推 alice: 推推                                              08/03 12:01".to_string(),
+            "噓 bob: 不推                                                08/03 12:02".to_string()
+        ]);
+    let mut board = FakeBoard::new("Test").with_articles(2);
+    board.articles.push(article);
+
+    let mut connection = login(FakePtt::new().board(board));
+    connection.enter_board("Test").unwrap();
+
+    let article = connection.read_article(3).unwrap();
+    assert_eq!(article.header.author, "writer ()");
+    assert_eq!(article.header.board, "Test");
+    assert_eq!(article.header.title, "[心得] 很長的文章");
+    assert!(article.body_lines.iter().any(|line| line == "第 40 行"));
+    assert_eq!(article.pushes.len(), 2);
+    assert_eq!(article.pushes[1].author, "bob");
+    assert_eq!(article.id.unwrap().to_filename(), "M.1700000000.A.000");
+
+    // 讀完後回到文章列表
+    assert_eq!(connection.article_list().len(), 3);
+}
+
+#[test]
+fn wrong_password_is_reported() {
+    let server = FakePtt::new().start();
+    let mut connection = PttConnection::connect(server.config()).unwrap();
+    match connection.login("account", "wrong") {
+        Err(CrawlerError::UsernameOrPasswordWrong) => {},
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn login_gets_past_every_interstitial() {
+    let server = FakePtt::new()
+        .hurdle(LoginHurdle::Loading)
+        .hurdle(LoginHurdle::DuplicateLogin)
+        .hurdle(LoginHurdle::TooFrequent)
+        .hurdle(LoginHurdle::FailedAttempts)
+        .hurdle(LoginHurdle::MailboxFull)
+        .hurdle(LoginHurdle::Announcement)
+        .start();
+    let mut connection = PttConnection::connect(server.config()).unwrap();
+
+    let events = connection.login("account", "password").unwrap();
+    assert_eq!(events, vec![
+        LoginEvent::Loading,
+        LoginEvent::DuplicateLogin,
+        LoginEvent::TooFrequent,
+        LoginEvent::FailedAttempts,
+        LoginEvent::MailboxFull,
+        LoginEvent::Announcement(vec!["批踢踢 26 週年".to_string(), "感謝大家一路以來的支持".to_string()]),
+        LoginEvent::Welcome
+    ]);
+    assert_eq!(server.answers(), vec![
+        (LoginHurdle::DuplicateLogin, "y".to_string()),
+        (LoginHurdle::FailedAttempts, "y".to_string())
+    ]);
+    assert!(connection.get_screen().check_string("主功能表"));
+}
+
+#[test]
+fn duplicate_login_can_be_kept() {
+    let server = FakePtt::new().hurdle(LoginHurdle::DuplicateLogin).start();
+    let mut connection = PttConnection::connect(server.config().kick_duplicate_login(false)).unwrap();
+
+    let events = connection.login("account", "password").unwrap();
+    assert_eq!(events, vec![LoginEvent::DuplicateLogin, LoginEvent::Welcome]);
+    assert_eq!(server.answers(), vec![(LoginHurdle::DuplicateLogin, "n".to_string())]);
+}
+
+#[test]
+fn login_events_are_reported_even_when_login_fails() {
+    let mut ptt = FakePtt::new().hurdle(LoginHurdle::DuplicateLogin);
+    for _ in 0 .. 10 {
+        ptt = ptt.hurdle(LoginHurdle::MailboxFull);
+    }
+    let server = ptt.start();
+    let mut connection = PttConnection::connect(server.config()).unwrap();
+
+    // 畫面太多，當作卡住了
+    let mut events = vec![];
+    match connection.login_with("account", "password", |event| events.push(event.clone())) {
+        Err(CrawlerError::UnexpectedScreen { .. }) => {},
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert_eq!(events.len(), 11);
+    assert_eq!(events[0], LoginEvent::DuplicateLogin);
+    assert_eq!(events[10], LoginEvent::MailboxFull);
+}
+
+#[test]
+fn unknown_board_is_reported() {
+    let server = FakePtt::new().start();
+    let mut connection = PttConnection::connect(server.config().wait_timeout(Duration::from_secs(5))).unwrap();
+    connection.login("account", "password").unwrap();
+
+    // 回到主功能表就知道找不到看板，不用等到 wait_timeout
+    let start = Instant::now();
+    match connection.enter_board("NoSuchBoard") {
+        Err(CrawlerError::BoardNotFound(name)) => assert_eq!(name, "NoSuchBoard"),
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn expect_reports_which_screen_appeared() {
+    let server = FakePtt::new().start();
+    let mut connection = PttConnection::connect(server.config()).unwrap();
+    let prompt = expect::region((21, 22), (0, 20), "請輸入代號");
+    assert_eq!(connection.expect(&any![expect::text("主功能表"), prompt]).unwrap(), 1);
+
+    match connection.expect(&all![expect::text("主功能表"), expect::stable(Duration::from_millis(50))]) {
+        Err(CrawlerError::Timeout { waiting_for }) => assert_eq!(waiting_for, "主功能表 + 畫面 50 ms 沒有變化"),
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn navigation_does_not_wait_for_the_full_timeout() {
+    let server = FakePtt::new().start();
+    let config = server.config().wait_timeout(Duration::from_secs(3));
+    let mut connection = PttConnection::connect(config).unwrap();
+    connection.login("account", "password").unwrap();
+    connection.enter_board("Test").unwrap();
+
+    // 每個按鍵都等滿 wait_timeout 的話至少要 12 秒
+    let start = Instant::now();
+    connection.all_article_list().unwrap();
+    connection.read_article(2).unwrap();
+    connection.next_article_page().unwrap();
+    assert!(start.elapsed() < Duration::from_secs(3), "took {:?}", start.elapsed());
+}
+
+#[test]
+fn searches_within_a_board() {
+    let mut connection = login(FakePtt::new());
+    connection.enter_board("Gossiping").unwrap();
+
+    let indexes = |entries: Vec<ArticleListEntry>| -> Vec<u32> { entries.iter().map(|e| e.index.unwrap()).collect() };
+    assert_eq!(indexes(connection.search_title("測試文章 4").unwrap()), vec![4, 40, 41, 42, 43, 44, 45]);
+    assert_eq!(indexes(connection.search_author("USER7").unwrap()), vec![7]);
+    assert_eq!(indexes(connection.search_push_count(10).unwrap()), vec![1, 2]);
+    assert_eq!(indexes(connection.search_push_count(-10).unwrap()), vec![3]);
+
+    // 沒有結果時不能離開看板
+    assert!(connection.search_title("不存在").unwrap().is_empty());
+    assert!(connection.get_screen().row_text(0).contains("看板《Gossiping》"));
+    assert_eq!(connection.article_list().len(), 20);
+}
+
+#[test]
+fn searches_more_than_one_page_of_results() {
+    let mut connection = login(FakePtt::new());
+    connection.enter_board("Gossiping").unwrap();
+    assert_eq!(connection.search_title("[問卦]").unwrap().len(), 45);
+    assert_eq!(connection.all_article_list().unwrap().len(), 47);
+}
+
+#[test]
+fn jumps_to_an_article_id() {
+    let board = FakeBoard::new("Test").with_articles(30);
+    let aid = board.articles[4].aid();
+    let mut connection = login(FakePtt::new().board(board));
+    connection.enter_board("Test").unwrap();
+
+    let entry = connection.find_article_by_aid(&format!("#{}", aid)).unwrap().unwrap();
+    assert_eq!(entry.index, Some(5));
+    assert_eq!(entry.author, "user5");
+    assert_eq!(entry.id.unwrap().to_aid(), aid);
+
+    assert_eq!(connection.find_article_by_aid("1ZZZZZZZ").unwrap(), None);
+    assert_eq!(connection.article_list().len(), 20);
+}
+
+#[test]
+fn fills_in_article_ids() {
+    let mut board = FakeBoard::new("Test").with_articles(3);
+    board.pinned.push(FakeArticle::new("SYSOP", "[公告] 板規"));
+    let expected: Vec<String> = board.articles.iter().map(|a| a.url("Test")).collect();
+    let mut connection = login(FakePtt::new().board(board));
+    connection.enter_board("Test").unwrap();
+
+    let mut entries = connection.all_article_list().unwrap();
+    assert!(entries.iter().all(|entry| entry.id.is_none()));
+    connection.fill_article_ids(&mut entries).unwrap();
+
+    let urls: Vec<String> = entries[.. 3].iter().map(|entry| entry.id.unwrap().to_url("Test")).collect();
+    assert_eq!(urls, expected);
+    assert_eq!(ArticleId::from_filename("M.1700000002.A.002"), entries[1].id);
+    assert_eq!(entries[3].id, None);
+
+    // 查完後停在最後查的那一篇
+    assert_eq!(connection.selected_article_id().unwrap(), entries[2].id);
+    assert_eq!(connection.article_list().len(), 4);
+}
+
+#[test]
+fn lists_articles_with_their_urls() {
+    let board = FakeBoard::new("Test").with_articles(3);
+    let expected: Vec<Option<String>> = board.articles.iter().map(|a| Some(a.url("Test"))).collect();
+    let mut connection = login(FakePtt::new().board(board));
+    connection.enter_board("test").unwrap();
+
+    let entries = connection.all_article_list().unwrap();
+    assert!(entries.iter().all(|entry| entry.board == "Test" && entry.to_url().is_none()));
+
+    let entries = connection.all_article_list_with_ids().unwrap();
+    let urls: Vec<Option<String>> = entries.iter().map(ArticleListEntry::to_url).collect();
+    assert_eq!(urls, expected);
+}
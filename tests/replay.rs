@@ -0,0 +1,166 @@
+extern crate ptt_crawler;
+
+use std::env;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use ptt_crawler::{parse_article_list, ConnectionConfig, CrawlerError, Key, PttConnection, PushCount, Transport};
+use ptt_crawler::transport::{Direction, RecordingTransport, ReplayTransport, Session};
+
+// 從登入到進入八卦板的紀錄
+const SESSION: &str = "tests/data/login_gossiping.session";
+
+fn replay() -> PttConnection {
+    PttConnection::with_transport(Box::new(ReplayTransport::open(SESSION).unwrap()), ConnectionConfig::new()).unwrap()
+}
+
+fn outbound(session: &Session) -> Vec<u8> {
+    session.events.iter().filter(|e| e.direction == Direction::Outbound).flat_map(|e| e.data.clone()).collect()
+}
+
+#[test]
+fn replayed_session_logs_in_and_lists_articles() {
+    let mut connection = replay();
+    connection.login("account", "password").unwrap();
+    connection.go_to_first_board().unwrap();
+
+    let entries = connection.article_list();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0].index, Some(12345));
+    assert_eq!(entries[0].push_count, Some(PushCount::Explosion));
+    assert_eq!(entries[1].title, "R: [新聞] 颱風假");
+    assert_eq!(entries[2].push_count, Some(PushCount::Boo(1)));
+    assert_eq!(entries[3].index, None);
+
+    // 也可以直接解析自己的畫面
+    assert_eq!(parse_article_list(connection.get_screen()), entries);
+}
+
+#[test]
+fn unexpected_keystrokes_are_reported() {
+    match replay().login("someone", "password") {
+        Err(CrawlerError::ReplayMismatch { expected, actual }) => {
+            assert_eq!(expected, b"account");
+            assert_eq!(actual, b"someone");
+        },
+        result => panic!("unexpected result: {:?}", result.map(|_| ()))
+    }
+}
+
+#[test]
+fn writes_may_be_split_differently_from_the_recording() {
+    let mut transport = ReplayTransport::open(SESSION).unwrap();
+    transport.write(b"acc").unwrap();
+    transport.write(b"ount\r\npass").unwrap();
+    assert!(transport.write(b"wrong").is_err());
+}
+
+#[test]
+fn recorded_sessions_can_be_replayed() {
+    let path = env::temp_dir().join(format!("ptt-crawler-replay-{}.session", std::process::id()));
+    let transport = RecordingTransport::create(Box::new(ReplayTransport::open(SESSION).unwrap()), &path).unwrap();
+    let mut connection = PttConnection::with_transport(Box::new(transport), ConnectionConfig::new()).unwrap();
+    connection.login("account", "password").unwrap();
+    drop(connection);
+
+    let recorded = Session::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(outbound(&recorded), b"account\r\npassword\r\n ");
+
+    // 收到的資料應該跟原本的紀錄一樣
+    let original = Session::load(SESSION).unwrap();
+    let inbound = |session: &Session| -> Vec<Vec<u8>> {
+        session.events.iter().filter(|e| e.direction == Direction::Inbound).map(|e| e.data.clone()).collect()
+    };
+    let recorded_inbound = inbound(&recorded);
+    assert_eq!(recorded_inbound[..], inbound(&original)[.. recorded_inbound.len()]);
+
+    // 重新錄下來的 session 也能再重播一次
+    let mut connection = PttConnection::with_transport(Box::new(ReplayTransport::new(recorded)), ConnectionConfig::new()).unwrap();
+    connection.login("account", "password").unwrap();
+}
+
+#[test]
+fn session_files_round_trip() {
+    let session = Session::load(SESSION).unwrap();
+    assert_eq!(Session::parse(&session.to_string()).unwrap().to_string(), session.to_string());
+    assert!(Session::parse("0.1 ? 00").is_err());
+    assert!(Session::parse("0.1 < 0").is_err());
+}
+
+#[test]
+fn keys_are_sent_with_the_configured_interval() {
+    let session = Session::parse("0.000 > 61\n0.000 > 1b5b367e\n0.000 > a44ba8f60d0a\n").unwrap();
+    let config = ConnectionConfig::new().key_interval(Duration::from_millis(50));
+    let mut connection = PttConnection::with_transport(Box::new(ReplayTransport::new(session)), config).unwrap();
+
+    let start = Instant::now();
+    connection.send_keys(&[Key::Char('a'), Key::PageDown, Key::text("八卦"), Key::Enter]).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(150));
+    assert!(connection.send_keys(&[Key::Space]).is_err());
+}
+
+#[test]
+fn tiny_terminals_are_rejected() {
+    let config = ConnectionConfig::new().terminal_size(0, 0);
+    match PttConnection::with_transport(Box::new(ReplayTransport::open(SESSION).unwrap()), config) {
+        Err(CrawlerError::InvalidConfig(_)) => {},
+        other => panic!("{:?}", other.err())
+    }
+}
+
+#[test]
+fn screens_that_never_become_ready_are_errors() {
+    let page_down = |inbound: &str| {
+        let session = Session::parse(&format!("0.000 > 1b5b367e\n{}", inbound)).unwrap();
+        let mut connection = PttConnection::with_transport(Box::new(ReplayTransport::new(session)), ConnectionConfig::new()).unwrap();
+        connection.next_article_page()
+    };
+
+    // 最後一行出現「文章選讀」才算畫完
+    assert!(page_down("0.000 < 1b5b32343b3148a4e5b3b9bfefc5aa\n").is_ok());
+    match page_down("") {
+        Err(CrawlerError::Timeout { .. }) => {},
+        other => panic!("{:?}", other)
+    }
+    match page_down("0.000 < 68656c6c6f\n") {
+        Err(CrawlerError::UnexpectedScreen { snapshot, .. }) => assert!(snapshot.starts_with("hello")),
+        other => panic!("{:?}", other)
+    }
+}
+
+// 檢查讀取逾時至少有 1ms，telnet 遇到 0 會 panic
+struct CheckedTimeout(ReplayTransport);
+
+impl Transport for CheckedTimeout {
+    fn read_timeout(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, CrawlerError> {
+        assert!(timeout >= Duration::from_millis(1), "read timeout too short: {:?}", timeout);
+        self.0.read_timeout(timeout)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), CrawlerError> {
+        self.0.write(bytes)
+    }
+}
+
+#[test]
+fn read_timeouts_are_clamped() {
+    let session = Session::parse("0.000 > 1b5b367e\n0.000 < 68656c6c6f\n0.000 < 1b5b32343b3148a4e5b3b9bfefc5aa\n").unwrap();
+    // 最短的設定值，算出來的等待時間很容易變成 0
+    let config = ConnectionConfig::new().quiet_period(Duration::from_nanos(1)).wait_timeout(Duration::from_nanos(1));
+    let transport = CheckedTimeout(ReplayTransport::new(session));
+    let mut connection = PttConnection::with_transport(Box::new(transport), config).unwrap();
+    connection.next_article_page().unwrap();
+}
+
+#[test]
+fn a_stalled_board_search_is_a_timeout() {
+    // 輸入看板名稱之後伺服器就沒有回應了
+    let session = Session::parse("0.000 > 73\n0.000 < bdd0bfe9a44aacddaa4fa657bad9\n0.000 > 546573740d0a\n").unwrap();
+    let config = ConnectionConfig::new().wait_timeout(Duration::from_millis(50));
+    let mut connection = PttConnection::with_transport(Box::new(ReplayTransport::new(session)), config).unwrap();
+    match connection.enter_board("Test") {
+        Err(CrawlerError::Timeout { .. }) => {},
+        other => panic!("{:?}", other)
+    }
+}
@@ -0,0 +1,116 @@
+extern crate proptest;
+extern crate ptt_crawler;
+
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use ptt_crawler::Screen;
+use ptt_crawler::screen::ansi::{AnsiToken, AnsiTokenizer, EraseOption};
+
+// 模擬 PTT 看板列表的畫面，包含顏色、Big5、捲動範圍與被覆蓋的半個字
+const RECORDED: &[u8] = include_bytes!("data/board_list.ans");
+
+fn render(chunks: &[&[u8]], width: usize, height: usize) -> Screen {
+    let mut screen = Screen::with_size(width, height);
+    for chunk in chunks {
+        screen.process(chunk);
+    }
+    screen
+}
+
+// 在 splits 指定的位置把 bytes 切開
+fn split<'a>(bytes: &'a [u8], splits: &[Index]) -> Vec<&'a [u8]> {
+    let mut points: Vec<usize> = splits.iter().map(|i| i.index(bytes.len() + 1)).collect();
+    points.push(0);
+    points.push(bytes.len());
+    points.sort();
+    points.windows(2).map(|w| &bytes[w[0] .. w[1]]).collect()
+}
+
+fn assert_same_screen(left: &Screen, right: &Screen) -> Result<(), TestCaseError> {
+    prop_assert_eq!(left.snapshot(), right.snapshot());
+    prop_assert_eq!(left.get_cursor_position(), right.get_cursor_position());
+    prop_assert_eq!(left.get_attributes(), right.get_attributes());
+    for r in 0 .. left.height() {
+        for c in 0 .. left.width() {
+            prop_assert_eq!(left.cell(r, c), right.cell(r, c), "cell ({}, {})", r, c);
+        }
+    }
+    Ok(())
+}
+
+// 容易組成控制序列的片段，比純亂數更容易走到 tokenizer 的各個狀態
+fn fragment() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        prop::collection::vec(any::<u8>(), 1 .. 8),
+        Just(b"\x1b".to_vec()),
+        Just(b"\x1b[".to_vec()),
+        Just(b"\x1b[?".to_vec()),
+        Just(b"\x1b]".to_vec()),
+        Just(b"\x1bP".to_vec()),
+        Just(b"\x1b\\".to_vec()),
+        Just(b"\r\n".to_vec()),
+        Just(b"\xa4\xa4".to_vec()),
+        (0u32 .. 1000).prop_map(|n| n.to_string().into_bytes()),
+        Just(b";".to_vec()),
+        (0x40u8 ..= 0x7E).prop_map(|b| vec![b]),
+        prop::sample::select(vec![0x07u8, 0x08, 0x18, 0x1A, 0x1B]).prop_map(|b| vec![b])
+    ]
+}
+
+#[test]
+fn recorded_stream_renders_the_board_list() {
+    let screen = render(&[RECORDED], 80, 24);
+    assert!(screen.row_text(0).contains("看板《Gossiping》"));
+    assert!(screen.row_text(3).contains("有沒有八卦？"));
+    assert!(screen.row_text(4).starts_with("> 123451"));
+    assert!(screen.row_text(6).starts_with("     ★"));
+    assert!(screen.row_text(9).starts_with(" X文字  中"));
+    assert!(screen.row_text(21).starts_with("捲動後的新文章"));
+    assert_eq!(screen.get_cursor_position(), (23, 10));
+}
+
+proptest! {
+    #[test]
+    fn chunking_a_recorded_stream_does_not_change_the_screen(splits in prop::collection::vec(any::<Index>(), 0 .. 64)) {
+        let whole = render(&[RECORDED], 80, 24);
+        let chunked = render(&split(RECORDED, &splits), 80, 24);
+        assert_same_screen(&whole, &chunked)?;
+    }
+
+    #[test]
+    fn chunking_random_streams_does_not_change_the_screen(
+            fragments in prop::collection::vec(fragment(), 0 .. 200),
+            splits in prop::collection::vec(any::<Index>(), 0 .. 32)) {
+        let bytes = fragments.concat();
+        let whole = render(&[&bytes], 20, 6);
+        let chunked = render(&split(&bytes, &splits), 20, 6);
+        assert_same_screen(&whole, &chunked)?;
+    }
+
+    #[test]
+    fn random_bytes_never_panic(bytes in prop::collection::vec(any::<u8>(), 0 .. 4096),
+            width in 1usize .. 100, height in 1usize .. 30) {
+        let screen = render(&[&bytes], width, height);
+        screen.snapshot();
+        screen.to_html();
+    }
+
+    #[test]
+    fn escape_sequences_never_panic(fragments in prop::collection::vec(fragment(), 0 .. 400),
+            width in 1usize .. 100, height in 1usize .. 30) {
+        let screen = render(&[&fragments.concat()], width, height);
+        screen.snapshot();
+        screen.to_ansi();
+        screen.to_svg();
+    }
+}
+
+#[test]
+fn the_tokenizer_is_usable_on_its_own() {
+    let mut tokenizer = AnsiTokenizer::new();
+    let tokens = tokenizer.tokenize(b"A\x1b[2;3H\x1b[1");
+    assert!(matches!(tokens[..], [AnsiToken::Text(b"A"), AnsiToken::CursorPosition(2, 3)]));
+    // 沒有結束的序列會留到下一次
+    assert!(matches!(tokenizer.tokenize(b"K")[..], [AnsiToken::EraseLine(EraseOption::EraseToBeginning)]));
+}
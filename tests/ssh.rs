@@ -0,0 +1,158 @@
+extern crate ptt_crawler;
+extern crate rand;
+extern crate russh;
+extern crate tokio;
+
+use std::sync::Arc;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use russh::{Channel, ChannelId};
+use russh::keys::{Algorithm, HashAlg, PrivateKey};
+use russh::server::{self, Auth, ChannelOpenHandle, Msg, Server, Session};
+use tokio::net::TcpListener;
+
+use ptt_crawler::{ConnectionConfig, CrawlerError, PttConnection};
+use ptt_crawler::screen::{Big5, ScreenEncoding};
+use ptt_crawler::transport::{SshTransport, Transport};
+
+// 模擬 PTT 的 sshd：只接受 bbsu，輸入兩次 Enter (帳號、密碼) 後就進到主功能表
+#[derive(Clone)]
+struct FakeSshd {
+    pty_requests: mpsc::Sender<(String, u32, u32)>,
+    received: Vec<u8>
+}
+
+impl Server for FakeSshd {
+    type Handler = FakeSshd;
+
+    fn new_client(&mut self, _: Option<std::net::SocketAddr>) -> FakeSshd {
+        self.clone()
+    }
+}
+
+impl server::Handler for FakeSshd {
+    type Error = russh::Error;
+
+    async fn auth_none(&mut self, user: &str) -> Result<Auth, Self::Error> {
+        if user == "bbsu" {
+            Ok(Auth::Accept)
+        } else {
+            Ok(Auth::reject())
+        }
+    }
+
+    async fn channel_open_session(&mut self, _: Channel<Msg>, reply: ChannelOpenHandle,
+            _: &mut Session) -> Result<(), Self::Error> {
+        reply.accept().await;
+        Ok(())
+    }
+
+    async fn pty_request(&mut self, channel: ChannelId, term: &str, col_width: u32, row_height: u32,
+            _: u32, _: u32, _: &[(russh::Pty, u32)], session: &mut Session) -> Result<(), Self::Error> {
+        let _ = self.pty_requests.send((term.to_string(), col_width, row_height));
+        session.channel_success(channel)
+    }
+
+    async fn shell_request(&mut self, channel: ChannelId, session: &mut Session) -> Result<(), Self::Error> {
+        session.data(channel, screen("請輸入代號，或以 guest 參觀："))
+    }
+
+    async fn data(&mut self, channel: ChannelId, data: &[u8], session: &mut Session) -> Result<(), Self::Error> {
+        let enters_before = self.received.iter().filter(|b| **b == b'\r').count();
+        self.received.extend_from_slice(data);
+        let enters = self.received.iter().filter(|b| **b == b'\r').count();
+        if enters_before < 2 && enters >= 2 {
+            session.data(channel, screen("歡迎您再度拜訪\r\n【主功能表】"))?;
+        } else if data != b"\n" {
+            session.data(channel, data.to_vec())?;
+        }
+        Ok(())
+    }
+}
+
+fn screen(text: &str) -> Vec<u8> {
+    let mut bytes = b"\x1b[2J\x1b[1;1H".to_vec();
+    bytes.extend(Big5.encode(text).unwrap());
+    bytes
+}
+
+// 在背景執行假的 sshd，回傳 port、主機金鑰的指紋與收到的 PTY 請求
+fn start_fake_sshd() -> (u16, String, mpsc::Receiver<(String, u32, u32)>) {
+    let (port_tx, port_rx) = mpsc::channel();
+    let (pty_tx, pty_rx) = mpsc::channel();
+    let key = PrivateKey::random(&mut rand::rng(), Algorithm::Ed25519).unwrap();
+    let fingerprint = key.public_key().fingerprint(HashAlg::Sha256).to_string();
+
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+        runtime.block_on(async move {
+            let config = server::Config {
+                keys: vec![key],
+                auth_rejection_time: Duration::from_millis(0),
+                ..Default::default()
+            };
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            port_tx.send(listener.local_addr().unwrap().port()).unwrap();
+
+            let mut sshd = FakeSshd {
+                pty_requests: pty_tx,
+                received: vec![]
+            };
+            sshd.run_on_socket(Arc::new(config), &listener).await.unwrap();
+        });
+    });
+
+    (port_rx.recv().unwrap(), fingerprint, pty_rx)
+}
+
+fn config(port: u16, user: &str, fingerprint: &str) -> ConnectionConfig {
+    ConnectionConfig::new().host("127.0.0.1").ssh(user).port(port).ssh_host_key(fingerprint)
+}
+
+#[test]
+fn ssh_transport_requests_a_pty_matching_the_screen() {
+    let (port, fingerprint, pty_requests) = start_fake_sshd();
+    let mut transport = SshTransport::connect(&config(port, "bbsu", &fingerprint), "bbsu").unwrap();
+
+    let (_, width, height) = pty_requests.recv_timeout(Duration::from_secs(3)).unwrap();
+    assert_eq!((width, height), (80, 24));
+
+    let welcome = transport.read_timeout(Duration::from_secs(3)).unwrap().unwrap();
+    assert_eq!(welcome, screen("請輸入代號，或以 guest 參觀："));
+
+    transport.write(b"guest").unwrap();
+    assert_eq!(transport.read_timeout(Duration::from_secs(3)).unwrap(), Some(b"guest".to_vec()));
+    assert_eq!(transport.read_timeout(Duration::from_millis(100)).unwrap(), None);
+}
+
+#[test]
+fn ssh_transport_rejects_unknown_users() {
+    let (port, fingerprint, _) = start_fake_sshd();
+    assert!(SshTransport::connect(&config(port, "root", &fingerprint), "root").is_err());
+}
+
+#[test]
+fn ssh_transport_rejects_unknown_host_keys() {
+    let (port, fingerprint, _) = start_fake_sshd();
+
+    // 沒有設定主機金鑰時也要拒絕
+    let unpinned = ConnectionConfig::new().host("127.0.0.1").ssh("bbsu").port(port);
+    let pinned_elsewhere = config(port, "bbsu", "SHA256:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+    for config in [unpinned, pinned_elsewhere] {
+        match SshTransport::connect(&config, "bbsu") {
+            Err(CrawlerError::UnknownHostKey(seen)) => assert_eq!(seen, fingerprint),
+            Err(e) => panic!("{}", e),
+            Ok(_) => panic!("連到了不信任的主機")
+        }
+    }
+}
+
+#[test]
+fn login_runs_unchanged_over_ssh() {
+    let (port, fingerprint, _) = start_fake_sshd();
+    let mut connection = PttConnection::connect(config(port, "bbsu", &fingerprint)).unwrap();
+
+    connection.login("account", "password").unwrap();
+}
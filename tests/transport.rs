@@ -0,0 +1,103 @@
+extern crate ptt_crawler;
+extern crate tungstenite;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use tungstenite::Message;
+use tungstenite::handshake::server::{Callback, ErrorResponse, Request, Response};
+
+use ptt_crawler::ConnectionConfig;
+use ptt_crawler::transport::{Transport, TelnetTransport, WebSocketTransport};
+
+// "請輸入代號" 的 Big5 編碼
+const WELCOME: &[u8] = b"\x1b[2J\xbd\xd0\xbf\xe9\xa4\x4a\xa5\x4e\xb8\xb9";
+
+// 記錄 client 送來的 Origin header
+struct OriginRecorder(mpsc::Sender<Option<String>>);
+
+impl Callback for OriginRecorder {
+    fn on_request(self, request: &Request, response: Response) -> Result<Response, ErrorResponse> {
+        let origin = request.headers().get("Origin").map(|v| v.to_str().unwrap().to_string());
+        self.0.send(origin).unwrap();
+        Ok(response)
+    }
+}
+
+#[test]
+fn websocket_transport_reads_and_writes_binary_frames() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (done_tx, done_rx) = mpsc::channel();
+    let (origin_tx, origin_rx) = mpsc::channel();
+
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut socket = tungstenite::accept_hdr(stream, OriginRecorder(origin_tx)).unwrap();
+
+        socket.send(Message::Binary(WELCOME.to_vec())).unwrap();
+        // 把收到的資料原封不動傳回去
+        let message = socket.read().unwrap();
+        socket.send(message).unwrap();
+
+        done_rx.recv().unwrap();
+    });
+
+    let mut transport = WebSocketTransport::connect(&format!("ws://127.0.0.1:{}/bbs", port)).unwrap();
+    assert_eq!(transport.read_timeout(Duration::from_secs(3)).unwrap(), Some(WELCOME.to_vec()));
+
+    transport.write(b"guest\r").unwrap();
+    assert_eq!(transport.read_timeout(Duration::from_secs(3)).unwrap(), Some(b"guest\r".to_vec()));
+
+    // 伺服器沒有再送資料時應該要 timeout
+    assert_eq!(transport.read_timeout(Duration::from_millis(100)).unwrap(), None);
+
+    done_tx.send(()).unwrap();
+    server.join().unwrap();
+    assert_eq!(origin_rx.recv().unwrap(), Some("https://term.ptt.cc".to_string()));
+}
+
+#[test]
+fn telnet_transport_reads_and_writes_raw_bytes() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (done_tx, done_rx) = mpsc::channel();
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        stream.write_all(WELCOME).unwrap();
+
+        let mut buffer = [0u8; 6];
+        stream.read_exact(&mut buffer).unwrap();
+        done_rx.recv().unwrap();
+        buffer.to_vec()
+    });
+
+    let config = ConnectionConfig::new().host("127.0.0.1").port(port);
+    let mut transport = TelnetTransport::connect(&config).unwrap();
+    assert_eq!(transport.read_timeout(Duration::from_secs(3)).unwrap(), Some(WELCOME.to_vec()));
+    assert_eq!(transport.read_timeout(Duration::from_millis(100)).unwrap(), None);
+
+    transport.write(b"guest\r").unwrap();
+    done_tx.send(()).unwrap();
+    assert_eq!(server.join().unwrap(), b"guest\r".to_vec());
+}
+
+#[test]
+fn telnet_transport_reports_a_closed_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        stream.write_all(WELCOME).unwrap();
+    });
+
+    let config = ConnectionConfig::new().host("127.0.0.1").port(port);
+    let mut transport = TelnetTransport::connect(&config).unwrap();
+    server.join().unwrap();
+    assert_eq!(transport.read_timeout(Duration::from_secs(3)).unwrap(), Some(WELCOME.to_vec()));
+    assert!(transport.read_timeout(Duration::from_secs(3)).is_err());
+}